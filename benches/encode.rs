@@ -0,0 +1,78 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+
+//! encode/decode throughput for primitives and collections
+//!
+//! Run with `cargo bench`, baseline numbers are documented in doc/benchmarks.md
+
+use compact_encoding::*;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::collections::HashMap;
+use std::hint::black_box;
+
+/// encode with the two-pass State path: pre_encode, alloc, encode
+fn encode_state<T: Encode>(value: &T) -> State {
+    let mut state = State::new();
+    value.pre_encode(&mut state);
+    state.alloc();
+    value.encode(&mut state).unwrap();
+    state
+}
+
+/// benchmark encoding and decoding of `value` with all available paths
+fn bench_value<T: Encode + Decode>(c: &mut Criterion, name: &str, value: &T) {
+    let buffer = encode_to_vec(value).unwrap();
+    let mut group = c.benchmark_group(name);
+    group.throughput(Throughput::Bytes(buffer.len() as u64));
+
+    group.bench_function(BenchmarkId::new("encode", "state"), |b| {
+        b.iter(|| encode_state(black_box(value)))
+    });
+    group.bench_function(BenchmarkId::new("encode", "vec_writer"), |b| {
+        b.iter(|| {
+            let mut out = Vec::new();
+            encode_into_vec(black_box(value), &mut out).unwrap();
+            out
+        })
+    });
+    group.bench_function(BenchmarkId::new("decode", "state"), |b| {
+        b.iter_batched(
+            || State::from_buffer(buffer.clone()),
+            |mut state| T::decode(&mut state).unwrap(),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+fn primitives(c: &mut Criterion) {
+    bench_value(c, "u64", &0x0123_4567_89AB_CDEFu64);
+    bench_value(c, "string", &"compact-encoding ".repeat(8));
+}
+
+fn collections(c: &mut Criterion) {
+    let vec: Vec<u32> = (0..10_000).collect();
+    bench_value(c, "vec_u32_10k", &vec);
+
+    // streaming decode without collecting into a Vec
+    let buffer = encode_to_vec(&vec).unwrap();
+    c.bench_function("vec_u32_10k/decode/iter", |b| {
+        b.iter_batched(
+            || State::from_buffer(buffer.clone()),
+            |mut state| {
+                state
+                    .decode_iter::<u32>()
+                    .unwrap()
+                    .map(|value| value.unwrap() as u64)
+                    .sum::<u64>()
+            },
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    let map: HashMap<u32, String> = (0..1_000).map(|i| (i, format!("value {i}"))).collect();
+    bench_value(c, "hash_map_1k", &map);
+}
+
+criterion_group!(benches, primitives, collections);
+criterion_main!(benches);
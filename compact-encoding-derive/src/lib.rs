@@ -0,0 +1,108 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+
+//! derive macro for the Encode and Decode traits of compact-encoding
+//!
+//! `#[derive(CompactEncoding)]` implements Encode and Decode for structs.
+//! The fields are encoded in declaration order without names or additional headers,
+//! for named structs, tuple structs, newtype structs and unit structs alike.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, parse_quote, Data, DeriveInput, Fields, Generics, Index};
+
+#[proc_macro_derive(CompactEncoding)]
+pub fn derive_compact_encoding(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                input,
+                "CompactEncoding can only be derived for structs",
+            ))
+        }
+    };
+    let name = &input.ident;
+
+    // access to each field by name or by position
+    let members: Vec<TokenStream> = match fields {
+        Fields::Named(named) => named
+            .named
+            .iter()
+            .map(|field| {
+                let ident = &field.ident;
+                quote!(#ident)
+            })
+            .collect(),
+        Fields::Unnamed(unnamed) => (0..unnamed.unnamed.len())
+            .map(|index| {
+                let index = Index::from(index);
+                quote!(#index)
+            })
+            .collect(),
+        Fields::Unit => vec![],
+    };
+    let types: Vec<_> = fields.iter().map(|field| &field.ty).collect();
+
+    let encode_generics = add_bounds(&input.generics, quote!(::compact_encoding::Encode));
+    let (impl_generics, ty_generics, where_clause) = encode_generics.split_for_impl();
+    let encode_impl = quote! {
+        impl #impl_generics ::compact_encoding::Encode for #name #ty_generics #where_clause {
+            fn pre_encode(&self, state: &mut ::compact_encoding::State) {
+                #( ::compact_encoding::Encode::pre_encode(&self.#members, state); )*
+            }
+
+            fn encode(
+                &self,
+                state: &mut ::compact_encoding::State,
+            ) -> ::compact_encoding::error::EncodeResult {
+                #( ::compact_encoding::Encode::encode(&self.#members, state)?; )*
+                Ok(())
+            }
+        }
+    };
+
+    // decode into local variables first, as the fields are decoded in declaration order
+    let variables: Vec<_> = (0..types.len())
+        .map(|index| format_ident!("field_{}", index))
+        .collect();
+    let construct = match fields {
+        Fields::Named(_) => quote!(Self { #( #members: #variables ),* }),
+        Fields::Unnamed(_) => quote!(Self( #( #variables ),* )),
+        Fields::Unit => quote!(Self),
+    };
+    let decode_generics = add_bounds(&input.generics, quote!(::compact_encoding::Decode));
+    let (impl_generics, ty_generics, where_clause) = decode_generics.split_for_impl();
+    let decode_impl = quote! {
+        impl #impl_generics ::compact_encoding::Decode for #name #ty_generics #where_clause {
+            fn decode(
+                state: &mut ::compact_encoding::State,
+            ) -> ::compact_encoding::error::DecodeResultT<Self> {
+                #( let #variables = <#types as ::compact_encoding::Decode>::decode(state)?; )*
+                Ok(#construct)
+            }
+        }
+    };
+
+    Ok(quote! {
+        #encode_impl
+        #decode_impl
+    })
+}
+
+/// add `bound` to each type parameter
+fn add_bounds(generics: &Generics, bound: TokenStream) -> Generics {
+    let mut generics = generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(parse_quote!(#bound));
+    }
+    generics
+}
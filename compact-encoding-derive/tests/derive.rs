@@ -0,0 +1,121 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+
+use compact_encoding::error::DecodeError;
+use compact_encoding::{
+    encode_to_vec, Batch, CompactEncoding, Decode, Encode, EncodeDyn, State, TypeTag,
+};
+
+/// encode `value` and decode it back as `T`, requiring the full buffer to be consumed
+fn round_trip<T: Encode + Decode>(value: &T) -> Result<T, DecodeError> {
+    let mut state = State::new();
+    value.pre_encode(&mut state);
+    state.alloc();
+    value.encode(&mut state).expect("could not encode");
+
+    state.start = 0;
+    let decoded = T::decode(&mut state);
+    assert_eq!(state.remaining(), 0);
+    decoded
+}
+
+#[derive(Debug, PartialEq, CompactEncoding)]
+struct Named {
+    id: u8,
+    name: String,
+    tags: Vec<u16>,
+}
+
+impl TypeTag for Named {
+    const TYPE_ID: u32 = 1;
+}
+
+#[derive(Debug, PartialEq, CompactEncoding)]
+struct Id(u64, [u8; 8]);
+
+#[derive(Debug, PartialEq, CompactEncoding)]
+struct Wrapper(String);
+
+#[derive(Debug, PartialEq, CompactEncoding)]
+struct Unit;
+
+#[derive(Debug, PartialEq, CompactEncoding)]
+struct Generic<T> {
+    value: T,
+    next: Vec<u8>,
+}
+
+#[test]
+fn test_named_struct() {
+    let value = Named {
+        id: 7,
+        name: "abc".into(),
+        tags: vec![1, 300],
+    };
+    // fields are encoded in declaration order without names
+    let mut expected = encode_to_vec(&7u8).unwrap();
+    expected.extend(encode_to_vec(&"abc").unwrap());
+    expected.extend(encode_to_vec(&vec![1u16, 300]).unwrap());
+    assert_eq!(encode_to_vec(&value), Ok(expected));
+    assert_eq!(round_trip(&value), Ok(value));
+}
+
+#[test]
+fn test_tuple_struct() {
+    let value = Id(u64::MAX, *b"abcdefgh");
+    assert_eq!(encode_to_vec(&value).unwrap().len(), 9 + 8);
+    assert_eq!(round_trip(&value), Ok(value));
+}
+
+#[test]
+fn test_newtype_struct() {
+    let value = Wrapper("wrapped".into());
+    // same encoding as the wrapped value
+    assert_eq!(encode_to_vec(&value), encode_to_vec(&"wrapped"));
+    assert_eq!(round_trip(&value), Ok(value));
+}
+
+#[test]
+fn test_unit_struct() {
+    assert_eq!(encode_to_vec(&Unit), Ok(vec![]));
+    assert_eq!(Unit::decode(&mut State::from_buffer(vec![])), Ok(Unit));
+}
+
+#[test]
+fn test_generic_struct() {
+    let value = Generic {
+        value: Wrapper("inner".into()),
+        next: vec![1, 2],
+    };
+    assert_eq!(round_trip(&value), Ok(value));
+}
+
+#[test]
+fn test_truncated() {
+    let mut buffer = encode_to_vec(&Id(1, [0; 8])).unwrap();
+    buffer.pop();
+    assert_eq!(
+        Id::decode(&mut State::from_buffer(buffer)),
+        Err(DecodeError::BufferTooSmall)
+    );
+}
+
+#[test]
+fn test_batch() {
+    let records: Vec<Named> = (0..10)
+        .map(|id| Named {
+            id,
+            name: format!("record {id}"),
+            tags: vec![id.into()],
+        })
+        .collect();
+    let batch = Batch::new(records);
+    assert_eq!(round_trip(&batch).as_ref(), Ok(&batch));
+
+    // the count is encoded once and no type id is repeated per record
+    let dynamic: Vec<&dyn EncodeDyn> = batch.0.iter().map(|r| r as &dyn EncodeDyn).collect();
+    let batch_size = encode_to_vec(&batch).unwrap().len();
+    let dynamic_size = encode_to_vec(&dynamic).unwrap().len();
+    assert_eq!(batch_size + batch.0.len(), dynamic_size);
+    assert_eq!(batch.into_inner().len(), 10);
+}
@@ -0,0 +1,33 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+
+//! decode arbitrary bytes as various types, decoding must fail gracefully instead of panicking
+
+#![no_main]
+
+use compact_encoding::{Decode, State};
+use libfuzzer_sys::fuzz_target;
+use std::collections::{BTreeMap, HashMap};
+
+/// decode `data` as T, starting from the beginning of the buffer
+fn decode<T: Decode>(data: &[u8]) {
+    let mut state = State::from_buffer(data.to_vec());
+    let _ = T::decode(&mut state);
+    assert!(state.position() <= data.len());
+}
+
+fuzz_target!(|data: &[u8]| {
+    decode::<u64>(data);
+    decode::<i64>(data);
+    decode::<char>(data);
+    decode::<String>(data);
+    decode::<Vec<u8>>(data);
+    decode::<Vec<u32>>(data);
+    decode::<Vec<String>>(data);
+    decode::<[u16; 4]>(data);
+    decode::<HashMap<u32, String>>(data);
+    decode::<HashMap<String, u32>>(data);
+    decode::<BTreeMap<u8, Vec<u8>>>(data);
+    decode::<compact_encoding::U32Array>(data);
+    decode::<compact_encoding::PackedBools>(data);
+});
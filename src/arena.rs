@@ -0,0 +1,43 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+//! decoding into a bumpalo arena
+//!
+//! Strings, buffers and sequences are allocated in the provided arena instead of the heap,
+//! so all decoded values are freed at once when the arena is dropped.
+//! The wire format is the same as for String, Vec<u8> and Vec<T>.
+
+use crate::error::*;
+use crate::{Decode, Reader, State, MAX_STRING_DECODE_SIZE};
+use bumpalo::Bump;
+
+/// decode a string into `arena`, like String::decode()
+pub fn decode_str_in<'b>(state: &mut State, arena: &'b Bump) -> DecodeResultT<&'b str> {
+    let bytes =
+        decode_bytes_in(state, arena, MAX_STRING_DECODE_SIZE).map_err(|error| match error {
+            DecodeError::ArrayTooLarge => DecodeError::StringTooLarge,
+            error => error,
+        })?;
+    std::str::from_utf8(bytes).map_err(|_| DecodeError::InvalidUtf8)
+}
+
+/// decode a buffer into `arena`, like Vec<u8>::decode()
+pub fn decode_buffer_in<'b>(state: &mut State, arena: &'b Bump) -> DecodeResultT<&'b [u8]> {
+    decode_bytes_in(state, arena, usize::MAX)
+}
+
+fn decode_bytes_in<'b>(state: &mut State, arena: &'b Bump, max: usize) -> DecodeResultT<&'b [u8]> {
+    let length = state.read_count(max)?;
+    Ok(arena.alloc_slice_copy(state.read_next(length)?))
+}
+
+/// decode a sequence into `arena`, like Vec<T>::decode()
+/// the elements are decoded with T::decode(), only the sequence itself is allocated in the arena
+pub fn decode_vec_in<'b, T: Decode>(
+    state: &mut State,
+    arena: &'b Bump,
+) -> DecodeResultT<bumpalo::collections::Vec<'b, T>> {
+    let length = state.read_count(usize::MAX)?;
+    let mut vec = bumpalo::collections::Vec::with_capacity_in(length, arena);
+    T::decode_extend(state, length, &mut vec)?;
+    Ok(vec)
+}
@@ -0,0 +1,102 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+//! compressed encoding of values
+//!
+//! The layout of a compressed value is:
+//!
+//! * codec tag as single byte, see [Compression]
+//! * length of the uncompressed encoding as usize
+//! * length of the compressed bytes as usize, followed by the compressed bytes
+//!
+//! The codecs are enabled by the sub-features `deflate` and `lz4`.
+//! Compression::None is always available.
+
+use crate::error::*;
+use crate::{encode_to_vec, Decode, Encode, State, MAX_STRING_DECODE_SIZE};
+
+/// compression codec used by encode_compressed()
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// the encoded value is stored as is
+    None,
+    /// deflate compression, requires feature `deflate`
+    #[cfg(feature = "deflate")]
+    Deflate,
+    /// lz4 block compression, requires feature `lz4`
+    #[cfg(feature = "lz4")]
+    Lz4,
+}
+
+const TAG_NONE: u8 = 0;
+const TAG_DEFLATE: u8 = 1;
+const TAG_LZ4: u8 = 2;
+
+impl Compression {
+    fn tag(&self) -> u8 {
+        match self {
+            Self::None => TAG_NONE,
+            #[cfg(feature = "deflate")]
+            Self::Deflate => TAG_DEFLATE,
+            #[cfg(feature = "lz4")]
+            Self::Lz4 => TAG_LZ4,
+        }
+    }
+
+    fn compress(&self, bytes: Vec<u8>) -> Vec<u8> {
+        match self {
+            Self::None => bytes,
+            #[cfg(feature = "deflate")]
+            Self::Deflate => miniz_oxide::deflate::compress_to_vec(&bytes, 6),
+            #[cfg(feature = "lz4")]
+            Self::Lz4 => lz4_flex::block::compress(&bytes),
+        }
+    }
+}
+
+/// encode `value` and compress the result with `codec`
+pub fn encode_compressed<T: Encode + ?Sized>(
+    value: &T,
+    codec: Compression,
+) -> Result<Vec<u8>, EncodeError> {
+    let encoded = encode_to_vec(value)?;
+    let length = encoded.len();
+    let compressed = codec.compress(encoded);
+
+    let mut state = State::new();
+    state.encode_append(&codec.tag())?;
+    state.encode_append(&length)?;
+    state.encode_append(&compressed)?;
+    Ok(state.buffer.take().unwrap_or_default())
+}
+
+/// decompress `bytes` written by encode_compressed() and decode a value of type T
+///
+/// Return TypeNotSupported if the codec is not enabled, TypeMismatch if the compressed
+/// bytes are corrupted and StringTooLarge if the uncompressed size exceeds the decode limit.
+pub fn decode_compressed<T: Decode>(bytes: &[u8]) -> DecodeResultT<T> {
+    let mut state = State::from_buffer(bytes.to_vec());
+    let tag = u8::decode(&mut state)?;
+    let length = usize::decode(&mut state)?;
+    if length > MAX_STRING_DECODE_SIZE {
+        return Err(DecodeError::StringTooLarge);
+    }
+    let compressed = state.read_lp_slice()?;
+    let encoded = match tag {
+        TAG_NONE => compressed.to_vec(),
+        #[cfg(feature = "deflate")]
+        TAG_DEFLATE => miniz_oxide::inflate::decompress_to_vec_with_limit(compressed, length)
+            .map_err(|_| DecodeError::TypeMismatch)?,
+        #[cfg(feature = "lz4")]
+        TAG_LZ4 => lz4_flex::block::decompress(compressed, length)
+            .map_err(|_| DecodeError::TypeMismatch)?,
+        #[cfg(not(feature = "deflate"))]
+        TAG_DEFLATE => return Err(DecodeError::TypeNotSupported),
+        #[cfg(not(feature = "lz4"))]
+        TAG_LZ4 => return Err(DecodeError::TypeNotSupported),
+        _ => return Err(DecodeError::TypeMismatch),
+    };
+    if encoded.len() != length {
+        return Err(DecodeError::TypeMismatch);
+    }
+    T::decode(&mut State::from_buffer(encoded))
+}
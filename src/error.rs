@@ -9,8 +9,12 @@ pub enum EncodeError {
     NoBuffer,
     /// rest of the buffer is too small to decode the expected type
     BufferTooSmall,
-    /// trying to encode a type which is not supported, e.g. u128
+    /// trying to encode a type which is not supported, e.g. a disabled compression codec
     TypeNotSupported,
+    /// length of the value does not fit into the length prefix
+    LengthTooLarge,
+    /// encode() wrote less bytes than allocated by pre_encode()
+    IncompleteEncode,
 }
 impl std::fmt::Display for EncodeError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -18,6 +22,10 @@ impl std::fmt::Display for EncodeError {
             Self::NoBuffer => write!(f, "no buffer allocated in State struct"),
             Self::BufferTooSmall => write!(f, "buffer is too small to decode the expected type"),
             Self::TypeNotSupported => write!(f, "the type is not supported by compact-encoding"),
+            Self::LengthTooLarge => write!(f, "the length does not fit into the length prefix"),
+            Self::IncompleteEncode => {
+                write!(f, "encode wrote less bytes than allocated by pre_encode")
+            }
         }
     }
 }
@@ -32,7 +40,7 @@ pub enum DecodeError {
     NoBuffer,
     /// buffer is too small to decode the expected type
     BufferTooSmall,
-    /// trying to decode a type which is not supported, e.g. u128
+    /// trying to decode a type which is not supported, e.g. a disabled compression codec
     TypeNotSupported,
     /// type does not match the expected type to decode
     TypeMismatch,
@@ -40,6 +48,12 @@ pub enum DecodeError {
     InvalidUtf8,
     /// encoded array is too large for decoding
     ArrayTooLarge,
+    /// encoded string is too large for decoding
+    StringTooLarge,
+    /// magic number in the buffer does not match the expected one
+    BadMagic,
+    /// length prefix declares more bytes or elements than are available in the buffer
+    InvalidLength { declared: usize, available: usize },
 }
 
 impl std::fmt::Display for DecodeError {
@@ -65,10 +79,52 @@ impl std::fmt::Display for DecodeError {
                     crate::MAX_ARRAY_DECODE_SIZE
                 )
             }
+            Self::StringTooLarge => {
+                write!(
+                    f,
+                    "the encoded string is bigger than the maximum supported string size of {} bytes",
+                    crate::MAX_STRING_DECODE_SIZE
+                )
+            }
+            Self::BadMagic => write!(f, "the magic number does not match the expected one"),
+            Self::InvalidLength {
+                declared,
+                available,
+            } => write!(
+                f,
+                "the length prefix declares {declared} elements, but only {available} bytes are available"
+            ),
+        }
+    }
+}
+
+impl DecodeError {
+    /// attach the byte offset at which decoding failed
+    pub fn at(self, offset: usize) -> DecodeErrorAt {
+        DecodeErrorAt {
+            error: self,
+            offset,
         }
     }
 }
 
+/// DecodeError together with the byte offset in the buffer at which decoding failed
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodeErrorAt {
+    pub error: DecodeError,
+    pub offset: usize,
+}
+
+impl std::fmt::Display for DecodeErrorAt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?} at offset {}: {}",
+            self.error, self.offset, self.error
+        )
+    }
+}
+
 /// shorthand type for decoding results
 /// must be parameterized with the type of the returned value
 pub type DecodeResultT<T> = Result<T, DecodeError>;
@@ -0,0 +1,122 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+//! compact encoding for append-only event logs
+//!
+//! Each entry is encoded as:
+//!
+//! * timestamp delta to the previous entry, zig-zag encoded as compact uint with the smallest
+//!   possible prefix (the first entry is relative to 0)
+//! * level as raw byte, without the compact uint prefix
+//! * message as string
+//!
+//! The delta wraps around, so any sequence of u64 timestamps can be encoded,
+//! but only close and ascending timestamps are encoded compactly.
+//! LogRecord implements Encode and Decode for this delta form of an entry.
+//! LogEntry holds the absolute timestamp, which depends on the previous entry,
+//! so it has no trait impls. Use LogWriter for encoding and LogReader for decoding,
+//! both track the previous timestamp and convert between LogEntry and LogRecord.
+
+use crate::error::*;
+use crate::{zig_zag_decode, zig_zag_encode, CompactU64, Decode, Encode, Reader, State, Writer};
+
+/// entry of an event log with the absolute timestamp
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LogEntry {
+    pub timestamp: u64,
+    pub level: u8,
+    pub message: String,
+}
+
+/// entry of an event log with the timestamp relative to the previous entry, as encoded
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LogRecord {
+    pub delta: i64,
+    pub level: u8,
+    pub message: String,
+}
+
+/// compact encoding for LogRecord
+impl Encode for LogRecord {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        CompactU64(zig_zag_encode(self.delta)).pre_encode(state);
+        state.end += 1;
+        self.message.pre_encode(state);
+    }
+
+    /// encode self into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        CompactU64(zig_zag_encode(self.delta)).encode(state)?;
+        state.write(&[self.level])?;
+        self.message.encode(state)
+    }
+}
+
+/// compact decoding for LogRecord
+impl Decode for LogRecord {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        let delta = zig_zag_decode(CompactU64::decode(state)?.0);
+        let level = state.read_next(1)?[0];
+        let message = String::decode(state)?;
+        Ok(Self {
+            delta,
+            level,
+            message,
+        })
+    }
+}
+
+/// encoder for a sequence of LogEntry values
+#[derive(Debug, Default)]
+pub struct LogWriter {
+    previous: u64,
+    state: State,
+}
+
+impl LogWriter {
+    /// create a new LogWriter with an empty buffer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// encode `entry` relative to the previously written entry and append it to the buffer
+    pub fn write(&mut self, entry: &LogEntry) -> EncodeResult {
+        self.state.encode_append(&LogRecord {
+            delta: entry.timestamp.wrapping_sub(self.previous) as i64,
+            level: entry.level,
+            message: entry.message.clone(),
+        })?;
+        self.previous = entry.timestamp;
+        Ok(())
+    }
+
+    /// return the encoded entries
+    pub fn finish(mut self) -> Vec<u8> {
+        self.state.buffer.take().unwrap_or_default()
+    }
+}
+
+/// decoder for a sequence of LogEntry values written by LogWriter
+#[derive(Debug, Default)]
+pub struct LogReader {
+    previous: u64,
+}
+
+impl LogReader {
+    /// create a new LogReader starting at timestamp 0
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// decode the next entry from `state` and reconstruct its absolute timestamp
+    pub fn read(&mut self, state: &mut State) -> DecodeResultT<LogEntry> {
+        let record = LogRecord::decode(state)?;
+        self.previous = self.previous.wrapping_add(record.delta as u64);
+        Ok(LogEntry {
+            timestamp: self.previous,
+            level: record.level,
+            message: record.message,
+        })
+    }
+}
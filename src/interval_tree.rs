@@ -0,0 +1,117 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+//! compact encoding for interval trees
+//!
+//! The intervals are encoded sorted by start, with delta encoded endpoints:
+//!
+//! * number of intervals as usize
+//! * each interval: start as delta to the start of the previous interval (the first one
+//!   is relative to 0), followed by the length `end - start`, both as compact uint
+//!
+//! Intervals are closed, `start <= end` is required.
+
+use crate::error::*;
+use crate::{CompactU64, Decode, Encode, State, MAX_ARRAY_DECODE_SIZE};
+
+/// set of closed intervals (start, end) supporting overlap queries
+///
+/// The intervals are kept sorted by start and end, duplicates are allowed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntervalTree {
+    intervals: Vec<(u64, u64)>,
+    /// maximum end of intervals[..=i], used to stop overlap queries early
+    max_end: Vec<u64>,
+}
+
+impl IntervalTree {
+    /// build the tree from `intervals`, endpoints are swapped if start > end
+    pub fn new(intervals: Vec<(u64, u64)>) -> Self {
+        let mut intervals: Vec<(u64, u64)> = intervals
+            .into_iter()
+            .map(|(start, end)| (start.min(end), start.max(end)))
+            .collect();
+        intervals.sort_unstable();
+        Self::from_sorted(intervals)
+    }
+
+    /// build the tree from intervals which are already sorted and valid
+    fn from_sorted(intervals: Vec<(u64, u64)>) -> Self {
+        let max_end = intervals
+            .iter()
+            .scan(0, |max, (_, end)| {
+                *max = (*max).max(*end);
+                Some(*max)
+            })
+            .collect();
+        Self { intervals, max_end }
+    }
+
+    /// return all intervals sorted by start and end
+    pub fn intervals(&self) -> &[(u64, u64)] {
+        &self.intervals
+    }
+
+    /// return all intervals which overlap the closed range start..=end
+    pub fn overlapping(&self, start: u64, end: u64) -> Vec<(u64, u64)> {
+        // intervals starting after `end` can not overlap
+        let candidates = self.intervals.partition_point(|interval| interval.0 <= end);
+        // intervals before `first` all end before `start`
+        let first = self.max_end[..candidates].partition_point(|max| *max < start);
+        self.intervals[first..candidates]
+            .iter()
+            .filter(|interval| interval.1 >= start)
+            .copied()
+            .collect()
+    }
+}
+
+/// compact encoding for IntervalTree
+impl Encode for IntervalTree {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        self.intervals.len().pre_encode(state);
+        let mut previous = 0;
+        for (start, end) in &self.intervals {
+            CompactU64(start - previous).pre_encode(state);
+            CompactU64(end - start).pre_encode(state);
+            previous = *start;
+        }
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        self.intervals.len().encode(state)?;
+        let mut previous = 0;
+        for (start, end) in &self.intervals {
+            CompactU64(start - previous).encode(state)?;
+            CompactU64(end - start).encode(state)?;
+            previous = *start;
+        }
+        Ok(())
+    }
+}
+
+/// compact decoding for IntervalTree
+/// return TypeMismatch if an endpoint overflows u64 or the intervals are not sorted
+impl Decode for IntervalTree {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        let count = state.read_count(MAX_ARRAY_DECODE_SIZE)?;
+        let mut intervals: Vec<(u64, u64)> = Vec::with_capacity(count);
+        let mut previous = 0u64;
+        for _ in 0..count {
+            let start = previous
+                .checked_add(CompactU64::decode(state)?.0)
+                .ok_or(DecodeError::TypeMismatch)?;
+            let end = start
+                .checked_add(CompactU64::decode(state)?.0)
+                .ok_or(DecodeError::TypeMismatch)?;
+            if intervals.last().is_some_and(|last| *last > (start, end)) {
+                return Err(DecodeError::TypeMismatch);
+            }
+            intervals.push((start, end));
+            previous = start;
+        }
+        Ok(Self::from_sorted(intervals))
+    }
+}
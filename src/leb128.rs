@@ -0,0 +1,94 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+//! LEB128 encoding for unsigned integers
+//!
+//! This is **not** part of the compact-encoding wire format.
+//! It is meant for interoperability with protobuf style systems,
+//! which encode integers in groups of 7 bit with a continuation bit.
+
+use crate::error::*;
+use crate::{Decode, Encode, Reader, State, Writer};
+
+/// wrapper struct for encoding unsigned integers as LEB128
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Leb128<T>(pub T);
+
+/// return the number of bytes required to encode `value` as LEB128
+fn encoded_len(mut value: u64) -> usize {
+    let mut len = 1;
+    while value >= 0x80 {
+        value >>= 7;
+        len += 1;
+    }
+    len
+}
+
+/// encode `value` as LEB128 into state.buffer
+fn encode_u64(mut value: u64, state: &mut State) -> EncodeResult {
+    while value >= 0x80 {
+        state.write(&[(value as u8 & 0x7F) | 0x80])?;
+        value >>= 7;
+    }
+    state.write(&[value as u8])
+}
+
+/// decode a LEB128 value which must fit into `bits` bits
+fn decode_u64(state: &mut State, bits: u32) -> DecodeResultT<u64> {
+    let mut value: u64 = 0;
+    let mut shift: u32 = 0;
+    loop {
+        let byte = state.read_next(1)?[0];
+        let payload = (byte & 0x7F) as u64;
+        // reject values which do not fit into the target type
+        if shift >= bits || (shift > 0 && payload >> (bits - shift) != 0) {
+            return Err(DecodeError::TypeMismatch);
+        }
+        value |= payload << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+        shift += 7;
+    }
+}
+
+/// LEB128 encoding for u32
+impl Encode for Leb128<u32> {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        state.end += encoded_len(self.0 as u64);
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        encode_u64(self.0 as u64, state)
+    }
+}
+
+/// LEB128 decoding for u32
+impl Decode for Leb128<u32> {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        decode_u64(state, u32::BITS).map(|value| Self(value as u32))
+    }
+}
+
+/// LEB128 encoding for u64
+impl Encode for Leb128<u64> {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        state.end += encoded_len(self.0);
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        encode_u64(self.0, state)
+    }
+}
+
+/// LEB128 decoding for u64
+impl Decode for Leb128<u64> {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        decode_u64(state, u64::BITS).map(Self)
+    }
+}
@@ -5,12 +5,29 @@
 #![doc(html_no_source)]
 #![warn(rustdoc::missing_crate_level_docs)]
 
+#[cfg(feature = "bumpalo")]
+pub mod arena;
+#[cfg(feature = "compression")]
+pub mod compression;
 pub mod error;
+pub mod event_log;
+pub mod interval_tree;
+pub mod leb128;
+pub mod merkle;
+pub mod net;
+pub mod sparse_matrix;
+pub mod trie;
 
 #[cfg(test)]
 mod tests;
 
 use crate::error::*;
+#[cfg(feature = "derive")]
+pub use compact_encoding_derive::CompactEncoding;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{BuildHasher, Hash};
+use std::rc::Rc;
+use std::sync::Arc;
 
 /// State contains the en-/decode buffer and index metadata.
 ///
@@ -21,11 +38,30 @@ use crate::error::*;
 /// * `start` is the byte offset to start encoding/decoding at.
 /// * `end` is the byte offset indicating the end of the buffer.
 /// * `buffer` is a Vec<u8>.
+///
+/// Additionally the byte order of fixed width fields (floats, U32Array, FixedU32, FixedU64)
+/// can be configured, it defaults to little-endian.
+/// The compact integer encoding is always little-endian.
 #[derive(Debug, PartialEq)]
 pub struct State {
     pub start: usize,
     end: usize,
     buffer: Option<Vec<u8>>,
+    byte_order: ByteOrder,
+}
+
+/// byte order of fixed width fields
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ByteOrder {
+    #[default]
+    LittleEndian,
+    BigEndian,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl State {
@@ -35,9 +71,33 @@ impl State {
             start: 0,
             end: 0,
             buffer: None,
+            byte_order: ByteOrder::LittleEndian,
+        }
+    }
+
+    /// create a new and empty State instance using big-endian fixed width fields
+    pub fn new_be() -> Self {
+        Self {
+            byte_order: ByteOrder::BigEndian,
+            ..Self::new()
+        }
+    }
+
+    /// create a State for decoding the content of `buffer`
+    pub fn from_buffer(buffer: Vec<u8>) -> Self {
+        Self {
+            start: 0,
+            end: buffer.len(),
+            buffer: Some(buffer),
+            byte_order: ByteOrder::LittleEndian,
         }
     }
 
+    /// set the byte order used for fixed width fields
+    pub fn set_byte_order(&mut self, byte_order: ByteOrder) {
+        self.byte_order = byte_order;
+    }
+
     /// allocate an internal buffer based on self.end
     pub fn alloc(&mut self) {
         // TODO: throw error if alloc fails?
@@ -50,31 +110,337 @@ impl State {
         // drop current buffer
         let _ = self.buffer.take();
     }
+
+    /// grow the internal buffer by `additional` bytes
+    pub fn reserve(&mut self, additional: usize) {
+        self.end += additional;
+        match &mut self.buffer {
+            Some(buffer) => buffer.resize(self.end, 0),
+            None => self.alloc(),
+        }
+    }
+
+    /// encode `value` at the end of the internal buffer
+    /// the buffer is grown as required, no separate pre_encode() and alloc() calls are needed
+    pub fn encode_append<T: Encode + ?Sized>(&mut self, value: &T) -> EncodeResult {
+        self.start = self.end;
+        self.reserve(encoded_size(value));
+        value.encode(self)
+    }
+
+    /// encode `value` at `offset`, overwriting previously written bytes, e.g. to backpatch a placeholder
+    /// self.start is restored afterwards
+    /// Return BufferTooSmall if the encoded value would extend beyond self.end
+    pub fn encode_at<T: Encode + ?Sized>(&mut self, offset: usize, value: &T) -> EncodeResult {
+        if offset > self.end || self.end - offset < encoded_size(value) {
+            return Err(EncodeError::BufferTooSmall);
+        }
+        let start = std::mem::replace(&mut self.start, offset);
+        let result = value.encode(self);
+        self.start = start;
+        result
+    }
+
+    /// check that encode() of `value` writes exactly the size reserved by pre_encode()
+    /// the encoded bytes are discarded
+    /// Return IncompleteEncode or BufferTooSmall if pre_encode() and encode() disagree
+    pub fn validate_encode<T: Encode + ?Sized>(value: &T) -> EncodeResult {
+        encode_to_vec_checked(value).map(|_| ())
+    }
+
+    /// append the encoded bytes (0 to other.end) of `other` at the end of the internal buffer
+    /// the buffer is grown as required
+    pub fn append_state(&mut self, other: &State) {
+        let offset = self.end;
+        self.reserve(other.end);
+        if let (Some(buffer), Some(other_buffer)) = (&mut self.buffer, &other.buffer) {
+            let length = other.end.min(other_buffer.len());
+            buffer[offset..offset + length].copy_from_slice(&other_buffer[..length]);
+        }
+        self.start = self.end;
+    }
+
+    /// shrink self.end and the internal buffer to `new_end`
+    /// does nothing if `new_end` is larger than self.end
+    pub fn truncate(&mut self, new_end: usize) {
+        if new_end > self.end {
+            return;
+        }
+        self.end = new_end;
+        self.start = self.start.min(new_end);
+        if let Some(buffer) = &mut self.buffer {
+            buffer.truncate(new_end);
+        }
+    }
+
+    /// render the buffer from 0 to self.end as space separated hex bytes
+    pub fn to_hex(&self) -> String {
+        self.hex_bytes().join(" ")
+    }
+
+    /// same as to_hex(), with a `|` marking the position of self.start
+    pub fn to_hex_with_cursor(&self) -> String {
+        let mut bytes = self.hex_bytes();
+        bytes.insert(self.start.min(bytes.len()), "|".into());
+        bytes.join(" ")
+    }
+
+    /// return true if the buffer from 0 to self.end equals `expected`
+    /// independent of the current position self.start, e.g. for asserting encoded bytes in tests
+    pub fn eq_bytes(&self, expected: &[u8]) -> bool {
+        match &self.buffer {
+            Some(buffer) => buffer.get(..self.end) == Some(expected),
+            None => self.end == 0 && expected.is_empty(),
+        }
+    }
+
+    fn hex_bytes(&self) -> Vec<String> {
+        match &self.buffer {
+            Some(buffer) => buffer[..self.end.min(buffer.len())]
+                .iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// return the number of bytes between self.start and self.end
+    pub fn remaining(&self) -> usize {
+        self.end.saturating_sub(self.start)
+    }
+
+    /// decode the element count of a collection
+    ///
+    /// Return InvalidLength if the count exceeds the remaining bytes
+    /// (every element occupies at least one byte) and ArrayTooLarge if it exceeds `max`.
+    pub fn read_count(&mut self, max: usize) -> DecodeResultT<usize> {
+        let count = usize::decode(self)?;
+        if count > self.remaining() {
+            Err(DecodeError::InvalidLength {
+                declared: count,
+                available: self.remaining(),
+            })
+        } else if count > max {
+            Err(DecodeError::ArrayTooLarge)
+        } else {
+            Ok(count)
+        }
+    }
+
+    /// return the current en-/decode position (same as self.start)
+    pub fn position(&self) -> usize {
+        self.start
+    }
+
+    /// move the en-/decode position to `pos`
+    /// return an error if `pos` is located after self.end
+    pub fn seek(&mut self, pos: usize) -> DecodeResultT<()> {
+        if pos > self.end {
+            return Err(DecodeError::BufferTooSmall);
+        }
+        self.start = pos;
+        Ok(())
+    }
+
+    /// read `magic.len()` bytes and compare them with `magic`, e.g. for per-chunk tags
+    /// return BadMagic if the bytes do not match, self.start is only advanced on success
+    pub fn expect_magic(&mut self, magic: &[u8]) -> DecodeResultT<()> {
+        if magic.is_empty() {
+            return Ok(());
+        }
+        let start = self.start;
+        if self.read_next(magic.len())? != magic {
+            self.start = start;
+            return Err(DecodeError::BadMagic);
+        }
+        Ok(())
+    }
+
+    /// decode a tagged value, the tag byte selects the decoder in `decoders`
+    ///
+    /// The decoder is called after the tag byte is consumed.
+    /// Return TypeMismatch without consuming the tag if there is no decoder for it.
+    pub fn decode_tagged<T>(
+        &mut self,
+        decoders: &HashMap<u8, fn(&mut State) -> DecodeResultT<T>>,
+    ) -> DecodeResultT<T> {
+        let decoder = decoders
+            .get(&self.peek_u8()?)
+            .ok_or(DecodeError::TypeMismatch)?;
+        self.start += 1;
+        decoder(self)
+    }
+
+    /// decode an unsigned integer which must use the smallest possible prefix
+    ///
+    /// Values are expected in the form written by usize::encode(), so each value has exactly one valid encoding.
+    /// Return TypeMismatch if the value could have been encoded more compactly or does not fit into T.
+    pub fn decode_canonical<T: TryFrom<u64>>(&mut self) -> DecodeResultT<T> {
+        let start = self.start;
+        let (value, min) = match self.peek_u8()? {
+            x if x <= U8_MAX_VALUE => (u8::decode(self)? as u64, 0),
+            U16_PREFIX => (u16::decode(self)? as u64, U8_MAX_VALUE as u64 + 1),
+            U32_PREFIX => (u32::decode(self)? as u64, u16::MAX as u64 + 1),
+            _ => (u64::decode(self)?, u32::MAX as u64 + 1),
+        };
+        match T::try_from(value) {
+            Ok(decoded) if value >= min => Ok(decoded),
+            _ => {
+                self.start = start;
+                Err(DecodeError::TypeMismatch)
+            }
+        }
+    }
+
+    /// decode a map which must be encoded with strictly increasing keys, e.g. HashMap<K, V> or BTreeMap<K, V>
+    ///
+    /// Only the unique sorted encoding is accepted for canonical maps.
+    /// Return TypeMismatch if a key is not greater than the previous key.
+    pub fn decode_canonical_map<K, V, M>(&mut self) -> DecodeResultT<M>
+    where
+        K: Decode + Ord,
+        V: Decode,
+        M: FromIterator<(K, V)>,
+    {
+        let count = self.read_count(MAX_ARRAY_DECODE_SIZE)?;
+        let mut entries: Vec<(K, V)> = Vec::with_capacity(count);
+        for _ in 0..count {
+            let key = K::decode(self)?;
+            if entries.last().is_some_and(|(previous, _)| *previous >= key) {
+                return Err(DecodeError::TypeMismatch);
+            }
+            entries.push((key, V::decode(self)?));
+        }
+        Ok(entries.into_iter().collect())
+    }
+
+    /// decode a value of type T and report the byte offset at which decoding failed
+    ///
+    /// The offset is the position of the field which could not be decoded.
+    pub fn decode_with_offset<T: Decode>(&mut self) -> Result<T, DecodeErrorAt> {
+        T::decode(self).map_err(|error| error.at(self.start))
+    }
+
+    /// return a reference to the next N bytes in the buffer without copying them
+    /// this is the borrowed counterpart to Fixed::<N>::decode()
+    pub fn decode_fixed_ref<const N: usize>(&mut self) -> DecodeResultT<&[u8; N]> {
+        self.read_next(N)?
+            .try_into()
+            .map_err(|_| DecodeError::BufferTooSmall)
+    }
+
+    /// return a reference to the bytes of a length-prefixed buffer without copying them
+    /// the bytes are not interpreted, e.g. to forward an encoded field as is
+    pub fn read_lp_slice(&mut self) -> DecodeResultT<&[u8]> {
+        let length = self.read_count(usize::MAX)?;
+        if length == 0 {
+            return Ok(&[]);
+        }
+        self.read_next(length)
+    }
+
+    /// return the bytes from self.start to self.end as Raw::Slice without copying them
+    /// afterwards the state is consumed, e.g. for deferred processing of a payload after a header
+    pub fn take_remaining_raw(&mut self) -> DecodeResultT<Raw<'_>> {
+        self.read_remaining().map(Raw::Slice)
+    }
+
+    /// decode the elements of an encoded Vec<T> lazily, one element per call to next()
+    ///
+    /// The element count is read immediately, the elements are decoded while iterating.
+    /// The iterator stops after the first error.
+    pub fn decode_iter<T: Decode>(&mut self) -> DecodeResultT<DecodeIter<'_, T>> {
+        let count = self.read_count(usize::MAX)?;
+        Ok(DecodeIter {
+            state: self,
+            count,
+            element: std::marker::PhantomData,
+        })
+    }
+
+    /// decode a value of type T from a length-prefixed region
+    ///
+    /// The length is decoded as usize and T is decoded within the following `length` bytes only.
+    /// Return an error if T does not consume exactly `length` bytes.
+    pub fn decode_scoped<T: Decode>(&mut self) -> DecodeResultT<T> {
+        self.decode_region(false)
+    }
+
+    /// decode a value of type T from a length-prefixed region, skipping unknown trailing bytes
+    ///
+    /// Same as decode_scoped(), but bytes in the region which are not consumed by T are ignored.
+    /// This allows decoding older versions of a type from a buffer containing a newer version
+    /// with additional trailing fields.
+    pub fn decode_scoped_lenient<T: Decode>(&mut self) -> DecodeResultT<T> {
+        self.decode_region(true)
+    }
+
+    fn decode_region<T: Decode>(&mut self, lenient: bool) -> DecodeResultT<T> {
+        let length = self.read_count(usize::MAX)?;
+        let region_end = self.start + length;
+        let parent_end = self.end;
+        self.end = region_end;
+        let value = T::decode(self);
+        self.end = parent_end;
+        let value = value?;
+        if self.start != region_end {
+            if !lenient {
+                return Err(DecodeError::TypeMismatch);
+            }
+            self.start = region_end;
+        }
+        Ok(value)
+    }
+}
+
+/// Iterator returned by State::decode_iter()
+#[derive(Debug)]
+pub struct DecodeIter<'a, T> {
+    state: &'a mut State,
+    count: usize,
+    element: std::marker::PhantomData<T>,
+}
+
+impl<T: Decode> Iterator for DecodeIter<'_, T> {
+    type Item = DecodeResultT<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.count == 0 {
+            return None;
+        }
+        let element = T::decode(self.state);
+        // stop after the first error
+        self.count = if element.is_ok() { self.count - 1 } else { 0 };
+        Some(element)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.count))
+    }
 }
 
 const U8_MAX_VALUE: u8 = 0xFC;
 const U16_PREFIX: u8 = 0xFD;
 const U32_PREFIX: u8 = 0xFE;
 const U64_PREFIX: u8 = 0xFF;
+// all bytes are used by the compact uint encoding already, the prefix of 128 bit integers
+// is a single byte value, which is rejected by all fixed width integer decoders (u16 to u64)
+const U128_PREFIX: u8 = U8_MAX_VALUE;
 const MAX_ARRAY_DECODE_SIZE: usize = 1048576;
+const MAX_STRING_DECODE_SIZE: usize = 16 * 1048576;
+// number of encode attempts with doubled buffer size in encode_growing()
+const MAX_ENCODE_GROWTH: u32 = 24;
 
 /// encode value from signed i64 into u64
 pub fn zig_zag_encode(value: i64) -> u64 {
-    let result = match value {
-        n if n < 0 => (2 * -n) - 1,
-        n if n == 0 => 0,
-        n => 2 * n,
-    };
-    result as u64
+    // bitwise variant of (2 * -n) - 1 for negative and 2 * n for positive values
+    // which does not overflow for i64::MIN
+    ((value << 1) ^ (value >> 63)) as u64
 }
 
 /// decode value from u64 to i64
 pub fn zig_zag_decode(value: u64) -> i64 {
-    match value {
-        n if n == 0 => n as i64,
-        n if (n & 1) == 0 => (n as i64) / 2,
-        n => -((n as i64) + 1) / 2,
-    }
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
 }
 
 /// Trait that indicates that a struct can be used as a destination to encode data too.
@@ -83,6 +449,11 @@ pub trait Writer {
     /// Write `bytes` to the underlying writer.
     /// Exactly `bytes.len()` bytes must be written, or else an error should be returned.
     fn write(&mut self, bytes: &[u8]) -> Result<(), EncodeError>;
+
+    /// Return the byte order to use for fixed width fields, defaults to little-endian.
+    fn byte_order(&self) -> ByteOrder {
+        ByteOrder::LittleEndian
+    }
 }
 
 /// State implements the Writer trait for writing to a binary buffer
@@ -103,27 +474,112 @@ impl Writer for State {
             Err(EncodeError::NoBuffer)
         }
     }
+
+    /// return the byte order configured for this State
+    fn byte_order(&self) -> ByteOrder {
+        self.byte_order
+    }
+}
+
+/// Vec<u8> implements the Writer trait by appending to the Vec, it never runs out of space
+impl Writer for Vec<u8> {
+    fn write(&mut self, bytes: &[u8]) -> EncodeResult {
+        self.extend_from_slice(bytes);
+        Ok(())
+    }
+}
+
+/// Writer which only counts the number of written bytes and discards them
+/// use encode_to_writer() to compute the encoded size of types without a usable pre_encode(),
+/// the count then comes from a temporary buffer holding the full encoding,
+/// so prefer encoded_size() if pre_encode() is exact
+#[derive(Debug, Default, PartialEq)]
+pub struct CountingWriter {
+    pub count: usize,
+}
+
+impl CountingWriter {
+    /// create a new CountingWriter with count 0
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// CountingWriter implements the Writer trait by adding up the length of written bytes
+impl Writer for CountingWriter {
+    fn write(&mut self, bytes: &[u8]) -> EncodeResult {
+        self.count += bytes.len();
+        Ok(())
+    }
+}
+
+/// Writer which feeds all written bytes into a Hasher and discards them
+///
+/// Encode writes into the buffer of a State, so values are not hashed while they are encoded.
+/// Hash a finished encoding by writing the output of encode_to_vec(), or use encode_to_writer(),
+/// which does the same with a temporary buffer.
+#[derive(Debug, Default)]
+pub struct HashingWriter<H: std::hash::Hasher> {
+    pub hasher: H,
+}
+
+impl<H: std::hash::Hasher> HashingWriter<H> {
+    /// create a new HashingWriter using `hasher`
+    pub fn new(hasher: H) -> Self {
+        Self { hasher }
+    }
+
+    /// return the hash value of all bytes written so far
+    pub fn finish(&self) -> u64 {
+        self.hasher.finish()
+    }
+}
+
+/// HashingWriter implements the Writer trait by passing each chunk to the Hasher
+impl<H: std::hash::Hasher> Writer for HashingWriter<H> {
+    fn write(&mut self, bytes: &[u8]) -> EncodeResult {
+        self.hasher.write(bytes);
+        Ok(())
+    }
+}
+
+/// mutable references to a Writer are Writers too, e.g. to pass `&mut dyn Writer` to encode_to_writer()
+impl<W: Writer + ?Sized> Writer for &mut W {
+    fn write(&mut self, bytes: &[u8]) -> EncodeResult {
+        (**self).write(bytes)
+    }
+
+    fn byte_order(&self) -> ByteOrder {
+        (**self).byte_order()
+    }
 }
 
 /// Trait that is used for reading from a buffer.
 /// Used by [Decode]
 pub trait Reader {
     /// Return a slice into the underlying buffer.
+    /// The returned slice must be exactly `size` bytes long.
     /// If remaining buffer is smaller than `size` an error must be returned.
-    fn read_next<'a>(&'a mut self, size: usize) -> DecodeResultT<&'a [u8]>;
+    fn read_next(&mut self, size: usize) -> DecodeResultT<&[u8]>;
 
     fn peek_u8(&self) -> DecodeResultT<u8>;
+
+    /// Return a slice of the remaining bytes in the underlying buffer.
+    /// Afterwards the reader is consumed completely.
+    fn read_remaining(&mut self) -> DecodeResultT<&[u8]>;
 }
 
 /// State implements Reader for extracting data from its binary buffer
 impl Reader for State {
-    fn read_next<'a>(&'a mut self, size: usize) -> DecodeResultT<&'a [u8]> {
-        if self.start >= self.end {
+    fn read_next(&mut self, size: usize) -> DecodeResultT<&[u8]> {
+        // never read beyond self.end, even if the buffer is larger
+        // reading zero bytes at the end of the buffer is valid, e.g. for [u8; 0]
+        if self.start > self.end || (self.end - self.start) < size {
             return Err(DecodeError::BufferTooSmall);
         };
         match &self.buffer {
             // TODO: may not be very performant
-            Some(buffer) if (buffer.len() - self.start) < size => Err(DecodeError::BufferTooSmall),
+            Some(buffer) if buffer.len() < self.start + size => Err(DecodeError::BufferTooSmall),
             Some(buffer) => {
                 let view = &buffer[self.start..self.start + size];
                 self.start += size;
@@ -142,137 +598,799 @@ impl Reader for State {
             None => Err(DecodeError::NoBuffer),
         }
     }
-}
-
-/// Trait which defines the required encoding functions
-pub trait Encode {
-    /// allocate the required size in State for current type
-    fn pre_encode(&self, state: &mut State);
 
-    /// encode n into state.buffer
-    /// return an error if state.buffer is not allocated or the buffer is too small
-    fn encode(&self, state: &mut State) -> EncodeResult;
+    /// get reference to all bytes between self.start and self.end
+    fn read_remaining(&mut self) -> DecodeResultT<&[u8]> {
+        match &self.buffer {
+            Some(buffer) if buffer.len() < self.end => Err(DecodeError::BufferTooSmall),
+            Some(buffer) => {
+                let view = &buffer[self.start.min(self.end)..self.end];
+                self.start = self.end;
+                Ok(view)
+            }
+            None => Err(DecodeError::NoBuffer),
+        }
+    }
 }
 
-/// Trait which defines the required decoding functions
-pub trait Decode: Sized {
-    /// return decode value at current buffer pointer
-    /// return an error if buffer size does not match or if header information is wrong
-    fn decode(state: &mut State) -> DecodeResultT<Self>;
+/// Reader over a sequence of chunks, e.g. from socket reads which split a message
+///
+/// Reads within a chunk return a slice into the chunk, reads across chunk boundaries
+/// are copied into an internal buffer.
+#[derive(Debug, Default)]
+pub struct ChainedReader<'a> {
+    chunks: Vec<&'a [u8]>,
+    chunk: usize,
+    offset: usize,
+    scratch: Vec<u8>,
+    // copy of the chunk with index window_chunk for decoding values within a chunk
+    window: State,
+    window_chunk: Option<usize>,
 }
 
-//
-// bool
-//
-
-/// compact encoding for bool
-impl Encode for bool {
-    /// allocate the required size in State for current type
-    fn pre_encode(&self, state: &mut State) {
-        state.end += std::mem::size_of::<u8>();
+impl<'a> ChainedReader<'a> {
+    /// create a new ChainedReader reading `chunks` in order
+    pub fn new(chunks: Vec<&'a [u8]>) -> Self {
+        Self {
+            chunks,
+            ..Default::default()
+        }
     }
 
-    /// encode n into state.buffer
-    /// requires state.buffer to be allocated first
-    fn encode(&self, state: &mut State) -> EncodeResult {
-        // encode true as 1u8 and false as 0u8
-        state.write(&[if *self { 1u8 } else { 0u8 }])
+    /// append `chunk` after the existing chunks
+    pub fn push(&mut self, chunk: &'a [u8]) {
+        self.chunks.push(chunk);
     }
-}
 
-/// compact decoding for u8
-impl Decode for bool {
-    fn decode(state: &mut State) -> DecodeResultT<Self> {
-        let value = state.read_next(std::mem::size_of::<u8>())?[0];
-        Ok(if value == 1 { true } else { false })
+    /// return the number of bytes left in all chunks
+    pub fn remaining(&self) -> usize {
+        self.chunks[self.chunk.min(self.chunks.len())..]
+            .iter()
+            .map(|chunk| chunk.len())
+            .sum::<usize>()
+            - self.offset
     }
-}
-
-//
-// unsigned integers
-//
 
-/// compact encoding for u8
-impl Encode for u8 {
-    /// allocate the required size in State for current type
-    fn pre_encode(&self, state: &mut State) {
-        if *self <= U8_MAX_VALUE {
-            state.end += std::mem::size_of::<u8>();
-        } else {
-            (*self as u16).pre_encode(state);
+    /// skip exhausted chunks, so self.chunk is the chunk containing the next byte
+    fn skip_empty(&mut self) {
+        while self.chunk < self.chunks.len() && self.offset == self.chunks[self.chunk].len() {
+            self.chunk += 1;
+            self.offset = 0;
         }
     }
 
-    /// encode n into state.buffer
-    /// requires state.buffer to be allocated first
-    fn encode(&self, state: &mut State) -> EncodeResult {
-        if *self <= U8_MAX_VALUE {
-            state.write(&[*self])
-        } else {
-            (*self as u16).encode(state)
+    /// decode a value of type T, which may span multiple chunks
+    ///
+    /// Decode is implemented on State, which owns its buffer, so decoding copies bytes:
+    /// * each chunk is copied once into an internal State, values within the chunk are decoded in place
+    /// * a value exceeding the current chunk is decoded from a temporary buffer, starting with the
+    ///   remainder of the current chunk. While decoding fails with BufferTooSmall or InvalidLength,
+    ///   the next chunk is appended and decoding is restarted, so a value spanning `n` chunks
+    ///   is decoded up to `n` times and the following chunks are copied completely.
+    pub fn decode<T: Decode>(&mut self) -> DecodeResultT<T> {
+        self.skip_empty();
+        let Some(chunk) = self.chunks.get(self.chunk).copied() else {
+            return T::decode(&mut State::from_buffer(vec![]));
+        };
+        if self.window_chunk != Some(self.chunk) {
+            self.window = State::from_buffer(chunk.to_vec());
+            self.window_chunk = Some(self.chunk);
+        }
+        self.window.start = self.offset;
+        match T::decode(&mut self.window) {
+            Ok(value) => {
+                self.offset = self.window.start;
+                return Ok(value);
+            }
+            Err(DecodeError::BufferTooSmall | DecodeError::InvalidLength { .. })
+                if self.chunk + 1 < self.chunks.len() => {}
+            Err(error) => return Err(error),
         }
-    }
-}
 
-/// compact decoding for u8
-impl Decode for u8 {
-    fn decode(state: &mut State) -> DecodeResultT<Self> {
-        let header = state.peek_u8()?;
-        if header <= U8_MAX_VALUE {
-            let value = state.read_next(std::mem::size_of::<u8>())?[0];
-            Ok(value)
-        } else {
-            u16::decode(state).map(|v| v as u8)
+        // the value spans multiple chunks, the number of attempts is limited by the chunks
+        let mut buffer = chunk[self.offset..].to_vec();
+        for next in self.chunk + 1..self.chunks.len() {
+            buffer.extend_from_slice(self.chunks[next]);
+            let mut state = State::from_buffer(buffer);
+            match T::decode(&mut state) {
+                Ok(value) => {
+                    self.read_next(state.start)?;
+                    return Ok(value);
+                }
+                Err(DecodeError::BufferTooSmall | DecodeError::InvalidLength { .. })
+                    if next + 1 < self.chunks.len() =>
+                {
+                    buffer = state.buffer.take().unwrap_or_default();
+                }
+                Err(error) => return Err(error),
+            }
         }
+        Err(DecodeError::BufferTooSmall)
     }
 }
 
-/// compact encoding for u16
-impl Encode for u16 {
-    /// allocate the required size in State for current type
-    fn pre_encode(&self, state: &mut State) {
-        state.end += 1 + (std::mem::size_of::<Self>());
+/// ChainedReader implements Reader for extracting data across chunk boundaries
+impl Reader for ChainedReader<'_> {
+    fn read_next(&mut self, size: usize) -> DecodeResultT<&[u8]> {
+        if self.remaining() < size {
+            return Err(DecodeError::BufferTooSmall);
+        }
+        self.skip_empty();
+        if size == 0 {
+            return Ok(&[]);
+        }
+        let current = self.chunks[self.chunk];
+        if current.len() - self.offset >= size {
+            let view = &current[self.offset..self.offset + size];
+            self.offset += size;
+            return Ok(view);
+        }
+        self.scratch.clear();
+        while self.scratch.len() < size {
+            let current = self.chunks[self.chunk];
+            let count = (size - self.scratch.len()).min(current.len() - self.offset);
+            self.scratch
+                .extend_from_slice(&current[self.offset..self.offset + count]);
+            self.offset += count;
+            self.skip_empty();
+        }
+        Ok(&self.scratch)
     }
 
-    /// encode n into state.buffer
-    /// requires state.buffer to be allocated first
-    fn encode(&self, state: &mut State) -> EncodeResult {
-        state.write(&[U16_PREFIX, *self as u8, (*self >> 8) as u8])
+    /// get next u8 value if one exists
+    fn peek_u8(&self) -> DecodeResultT<u8> {
+        self.chunks[self.chunk.min(self.chunks.len())..]
+            .iter()
+            .enumerate()
+            .find_map(|(index, chunk)| match index {
+                0 => chunk.get(self.offset),
+                _ => chunk.first(),
+            })
+            .copied()
+            .ok_or(DecodeError::BufferTooSmall)
+    }
+
+    /// get all remaining bytes, copied into one slice if they span multiple chunks
+    fn read_remaining(&mut self) -> DecodeResultT<&[u8]> {
+        let size = self.remaining();
+        self.read_next(size)
     }
 }
 
-/// compact decoding for u16
-impl Decode for u16 {
-    fn decode(state: &mut State) -> DecodeResultT<Self> {
-        let buffer = state.read_next(1 + std::mem::size_of::<Self>())?;
-        if buffer[0] == U16_PREFIX {
-            let mut value: Self = buffer[1] as u16;
-            value += buffer[2] as u16 * 256;
-            Ok(value)
-        } else {
-            Err(DecodeError::TypeMismatch)
+/// Reader which feeds all consumed bytes of a State into a Hasher, e.g. to verify a trailing checksum
+#[derive(Debug)]
+pub struct HashingReader<'a, H: std::hash::Hasher> {
+    state: &'a mut State,
+    pub hasher: H,
+}
+
+impl<'a, H: std::hash::Hasher> HashingReader<'a, H> {
+    /// create a new HashingReader reading from `state` using `hasher`
+    pub fn new(state: &'a mut State, hasher: H) -> Self {
+        Self { state, hasher }
+    }
+
+    /// decode a value of type T from the state and hash the consumed bytes
+    pub fn decode<T: Decode>(&mut self) -> DecodeResultT<T> {
+        let start = self.state.start;
+        let value = T::decode(self.state)?;
+        if let Some(buffer) = &self.state.buffer {
+            self.hasher.write(&buffer[start..self.state.start]);
         }
+        Ok(value)
+    }
+
+    /// return the hash value of all bytes consumed so far
+    pub fn finish(&self) -> u64 {
+        self.hasher.finish()
     }
 }
 
-/// compact encoding for u32
-impl Encode for u32 {
-    /// allocate the required size in State for current type
-    fn pre_encode(&self, state: &mut State) {
-        state.end += 1 + (std::mem::size_of::<Self>());
+/// HashingReader implements the Reader trait by passing each read chunk to the Hasher
+impl<H: std::hash::Hasher> Reader for HashingReader<'_, H> {
+    fn read_next(&mut self, size: usize) -> DecodeResultT<&[u8]> {
+        let view = self.state.read_next(size)?;
+        self.hasher.write(view);
+        Ok(view)
     }
 
-    /// encode n into state.buffer
-    /// requires state.buffer to be allocated first
-    fn encode(&self, state: &mut State) -> EncodeResult {
-        state.write(&[U32_PREFIX])?;
-        state.write(&encode_u32(*self))
+    fn peek_u8(&self) -> DecodeResultT<u8> {
+        self.state.peek_u8()
+    }
+
+    fn read_remaining(&mut self) -> DecodeResultT<&[u8]> {
+        let view = self.state.read_remaining()?;
+        self.hasher.write(view);
+        Ok(view)
     }
 }
 
-/// compact encoding for u32 number to byte array
-#[inline(always)]
-fn encode_u32(value: u32) -> [u8; 4] {
+/// Trait which defines the required encoding functions
+pub trait Encode {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State);
+
+    /// encode n into state.buffer
+    /// return an error if state.buffer is not allocated or the buffer is too small
+    fn encode(&self, state: &mut State) -> EncodeResult;
+
+    /// allocate the required size in State for a sequence of elements (without length)
+    /// used by Vec<T>, types can override this to provide a more efficient layout
+    fn pre_encode_slice(slice: &[Self], state: &mut State)
+    where
+        Self: Sized,
+    {
+        for element in slice.iter() {
+            element.pre_encode(state);
+        }
+    }
+
+    /// encode a sequence of elements (without length) into state.buffer
+    /// used by Vec<T>, types can override this to provide a more efficient layout
+    fn encode_slice(slice: &[Self], state: &mut State) -> EncodeResult
+    where
+        Self: Sized,
+    {
+        for element in slice.iter() {
+            element.encode(state)?;
+        }
+        Ok(())
+    }
+
+    /// allocate the required size in State for an array [Self; N]
+    /// used by [T; N], types can override this to provide a more efficient layout
+    fn pre_encode_array<const N: usize>(array: &[Self; N], state: &mut State)
+    where
+        Self: Sized,
+    {
+        N.pre_encode(state);
+        for element in array.iter() {
+            element.pre_encode(state);
+        }
+    }
+
+    /// encode an array [Self; N] into state.buffer
+    /// used by [T; N], types can override this to provide a more efficient layout
+    fn encode_array<const N: usize>(array: &[Self; N], state: &mut State) -> EncodeResult
+    where
+        Self: Sized,
+    {
+        N.encode(state)?;
+        for element in array.iter() {
+            element.encode(state)?;
+        }
+        Ok(())
+    }
+}
+
+/// Trait which defines the required decoding functions
+pub trait Decode: Sized {
+    /// return decode value at current buffer pointer
+    /// return an error if buffer size does not match or if header information is wrong
+    fn decode(state: &mut State) -> DecodeResultT<Self>;
+
+    /// decode a sequence of `length` elements encoded with Encode::encode_slice() and append them to `target`
+    /// used by Vec<T>, types can override this to provide a more efficient layout
+    /// `target` should reserve space for `length` elements beforehand
+    fn decode_extend<C: Extend<Self>>(
+        state: &mut State,
+        length: usize,
+        target: &mut C,
+    ) -> DecodeResultT<()> {
+        if length > MAX_ARRAY_DECODE_SIZE {
+            return Err(DecodeError::ArrayTooLarge);
+        }
+        for _ in 0..length {
+            target.extend(Some(Self::decode(state)?));
+        }
+        Ok(())
+    }
+
+    /// decode an array [Self; N] encoded with Encode::encode_array()
+    /// used by [T; N], types can override this to provide a more efficient layout
+    fn decode_array<const N: usize>(state: &mut State) -> DecodeResultT<[Self; N]> {
+        if usize::decode(state)? != N {
+            return Err(DecodeError::TypeMismatch);
+        }
+        let mut vec: Vec<Self> = Vec::with_capacity(N);
+        for _ in 0..N {
+            vec.push(Self::decode(state)?);
+        }
+        vec.try_into().map_err(|_| DecodeError::TypeMismatch)
+    }
+}
+
+/// Trait for decoding into an existing value, reusing its allocation
+pub trait DecodeInPlace: Decode {
+    /// decode value at current buffer pointer into `target`
+    /// `target` is cleared first, its capacity is reused if the decoded value fits
+    fn decode_in_place(state: &mut State, target: &mut Self) -> DecodeResultT<()>;
+}
+
+/// Trait for types which can only estimate their encoded size
+///
+/// Use encode_to_vec_with_hint() for encoding these types,
+/// pre_encode() is not used and does not need to be exact.
+pub trait SizeHint {
+    /// return the estimated number of bytes required for encoding
+    fn size_hint(&self) -> usize;
+}
+
+/// encode `value` into a newly allocated buffer
+/// runs pre_encode(), alloc() and encode() and returns the finished buffer, same as encode_to_vec()
+pub fn encode<T: Encode + ?Sized>(value: &T) -> Result<Vec<u8>, EncodeError> {
+    encode_to_vec(value)
+}
+
+/// decode a value of type T from the start of `bytes`
+/// bytes after the decoded value are ignored, same as decode_lenient()
+pub fn decode<T: Decode>(bytes: &[u8]) -> DecodeResultT<T> {
+    decode_lenient(bytes)
+}
+
+//
+// helpers
+//
+
+/// return the number of bytes required to encode `value`
+pub fn encoded_size<T: Encode + ?Sized>(value: &T) -> usize {
+    let mut state = State::new();
+    value.pre_encode(&mut state);
+    state.end
+}
+
+/// encode `value` into a newly allocated buffer
+pub fn encode_to_vec<T: Encode + ?Sized>(value: &T) -> Result<Vec<u8>, EncodeError> {
+    let mut state = State::new();
+    value.pre_encode(&mut state);
+    state.alloc();
+    value.encode(&mut state)?;
+    Ok(state.buffer.take().unwrap_or_default())
+}
+
+/// encode `value` into a newly allocated buffer with the capacity returned by size_hint()
+/// the buffer grows if the hint is too small, pre_encode() is not called
+/// return BufferTooSmall if the encoding does not fit into 2^23 times the hint (at least 16 bytes)
+pub fn encode_to_vec_with_hint<T: Encode + SizeHint + ?Sized>(
+    value: &T,
+) -> Result<Vec<u8>, EncodeError> {
+    encode_growing(value, value.size_hint(), ByteOrder::LittleEndian)
+}
+
+/// encode `value` into `writer`, e.g. a CountingWriter or HashingWriter
+/// the value is encoded into a temporary buffer first, which grows as needed, pre_encode() is not called
+/// the finished buffer is passed to the writer with a single write() call
+pub fn encode_to_writer<T: Encode + ?Sized, W: Writer + ?Sized>(
+    value: &T,
+    writer: &mut W,
+) -> EncodeResult {
+    writer.write(&encode_growing(value, 0, writer.byte_order())?)
+}
+
+/// encode `value` into a buffer starting with `capacity` bytes, which is doubled
+/// and encoding is restarted each time encode() runs out of space
+/// return BufferTooSmall if encode() still fails after MAX_ENCODE_GROWTH attempts
+fn encode_growing<T: Encode + ?Sized>(
+    value: &T,
+    capacity: usize,
+    byte_order: ByteOrder,
+) -> Result<Vec<u8>, EncodeError> {
+    let mut capacity = capacity.max(16);
+    for _ in 0..MAX_ENCODE_GROWTH {
+        let mut state = State::new();
+        state.set_byte_order(byte_order);
+        state.end = capacity;
+        state.alloc();
+        match value.encode(&mut state) {
+            Ok(()) => {
+                let mut buffer = state.buffer.take().unwrap_or_default();
+                buffer.truncate(state.start);
+                return Ok(buffer);
+            }
+            // BufferTooSmall may also be returned by a faulty encode(), so growing is limited
+            Err(EncodeError::BufferTooSmall) => match capacity.checked_mul(2) {
+                Some(next) if next <= isize::MAX as usize => capacity = next,
+                _ => break,
+            },
+            Err(error) => return Err(error),
+        }
+    }
+    Err(EncodeError::BufferTooSmall)
+}
+
+/// encode `value` into a newly allocated buffer and verify the size allocated by pre_encode()
+///
+/// Return IncompleteEncode if encode() wrote less bytes than allocated
+/// and BufferTooSmall if it tried to write more.
+/// This helps to find mismatches between pre_encode() and encode() of custom types.
+pub fn encode_to_vec_checked<T: Encode + ?Sized>(value: &T) -> Result<Vec<u8>, EncodeError> {
+    let mut state = State::new();
+    value.pre_encode(&mut state);
+    state.alloc();
+    value.encode(&mut state)?;
+    if state.start != state.end {
+        return Err(EncodeError::IncompleteEncode);
+    }
+    Ok(state.buffer.take().unwrap_or_default())
+}
+
+/// encode all items back-to-back into a newly allocated buffer, without length prefix
+/// use decode_all() for decoding
+pub fn encode_seq<T: Encode, I: IntoIterator<Item = T>>(iter: I) -> Result<Vec<u8>, EncodeError> {
+    let mut out = Vec::new();
+    for item in iter {
+        encode_into_vec(&item, &mut out)?;
+    }
+    Ok(out)
+}
+
+/// encode all items with their count, each item is prefixed with its encoded length
+/// use decode_lossy() for decoding, which can skip items that fail to decode
+pub fn encode_framed<T: Encode>(items: &[T]) -> Result<Vec<u8>, EncodeError> {
+    let mut out = Vec::new();
+    encode_into_vec(&items.len(), &mut out)?;
+    for item in items {
+        encode_into_vec(&encoded_size(item), &mut out)?;
+        encode_into_vec(item, &mut out)?;
+    }
+    Ok(out)
+}
+
+/// encode `value` and append it to `out`, existing content of `out` is kept
+/// the encoding is written directly into the grown tail of `out`, on errors `out` is restored
+pub fn encode_into_vec<T: Encode + ?Sized>(value: &T, out: &mut Vec<u8>) -> EncodeResult {
+    let offset = out.len();
+    out.resize(offset + encoded_size(value), 0);
+    let mut state = State::from_buffer(std::mem::take(out));
+    state.start = offset;
+    let result = value.encode(&mut state);
+    *out = state.buffer.take().unwrap_or_default();
+    if result.is_err() {
+        out.truncate(offset);
+    }
+    result
+}
+
+/// encode `value` into a new buffer, prefixed with a 4 byte `magic` number and a `version`
+pub fn encode_with_header<T: Encode + ?Sized>(
+    magic: [u8; 4],
+    version: u16,
+    value: &T,
+) -> Result<Vec<u8>, EncodeError> {
+    let mut state = State::new();
+    state.end += magic.len();
+    version.pre_encode(&mut state);
+    value.pre_encode(&mut state);
+    state.alloc();
+    state.write(&magic)?;
+    version.encode(&mut state)?;
+    value.encode(&mut state)?;
+    Ok(state.buffer.take().unwrap_or_default())
+}
+
+/// decode a value of type T from `bytes` encoded with encode_with_header()
+/// return the version from the header together with the value
+/// return DecodeError::BadMagic if the magic number does not match `magic`
+pub fn decode_with_header<T: Decode>(magic: [u8; 4], bytes: &[u8]) -> DecodeResultT<(u16, T)> {
+    let mut state = State::from_buffer(bytes.to_vec());
+    state.expect_magic(&magic)?;
+    let version = u16::decode(&mut state)?;
+    let value = T::decode(&mut state)?;
+    Ok((version, value))
+}
+
+/// check that `sentinel` can be told apart from escaped bytes, see encode_with_sentinel()
+fn assert_sentinel(sentinel: &[u8]) {
+    assert!(
+        sentinel.len() >= 2 && sentinel[1] != 0,
+        "sentinel requires at least 2 bytes and the second byte must not be 0"
+    );
+}
+
+/// encode `value` and append `sentinel` to mark the end of the message, e.g. for resynchronizing streams
+///
+/// Every occurrence of the first sentinel byte in the encoded value is escaped by appending a 0 byte,
+/// so the sentinel can not appear in the escaped payload.
+/// This costs one byte per occurrence, so pick a first byte which is rare in the payload.
+/// Corrupted data may still contain the sentinel, decode_until_sentinel() then returns an error
+/// for the affected messages.
+/// Panics if `sentinel` is shorter than 2 bytes or its second byte is 0.
+pub fn encode_with_sentinel<T: Encode + ?Sized>(
+    value: &T,
+    sentinel: &[u8],
+) -> Result<Vec<u8>, EncodeError> {
+    assert_sentinel(sentinel);
+    let escape = sentinel[0];
+    let encoded = encode_to_vec(value)?;
+    let mut out = Vec::with_capacity(encoded.len() + sentinel.len());
+    for byte in encoded {
+        out.push(byte);
+        if byte == escape {
+            out.push(0);
+        }
+    }
+    out.extend_from_slice(sentinel);
+    Ok(out)
+}
+
+/// decode a message written by encode_with_sentinel() and advance state.start past its sentinel
+///
+/// On errors state.start is still advanced past the sentinel, so the next call continues
+/// with the following message. Return TypeMismatch for invalid escape sequences and
+/// BufferTooSmall without advancing if no sentinel follows.
+/// Panics if `sentinel` is shorter than 2 bytes or its second byte is 0.
+pub fn decode_until_sentinel<T: Decode>(state: &mut State, sentinel: &[u8]) -> DecodeResultT<T> {
+    assert_sentinel(sentinel);
+    let escape = sentinel[0];
+    let bytes = state.read_remaining()?;
+    let mut frame = Vec::new();
+    let mut corrupted = false;
+    let mut index = 0;
+    let length = loop {
+        match bytes[index..].iter().position(|byte| *byte == escape) {
+            None => break None,
+            Some(offset) => {
+                frame.extend_from_slice(&bytes[index..index + offset]);
+                index += offset;
+            }
+        }
+        if bytes[index..].starts_with(sentinel) {
+            break Some(index + sentinel.len());
+        }
+        match bytes.get(index + 1) {
+            Some(0) => {
+                frame.push(escape);
+                index += 2;
+            }
+            // escape byte which is neither escaped nor the start of the sentinel,
+            // the next byte may start the sentinel and is not skipped
+            Some(_) => {
+                corrupted = true;
+                index += 1;
+            }
+            None => break None,
+        }
+    };
+    let Some(length) = length else {
+        state.start -= bytes.len();
+        return Err(DecodeError::BufferTooSmall);
+    };
+    state.start -= bytes.len() - length;
+    if corrupted {
+        return Err(DecodeError::TypeMismatch);
+    }
+    let mut frame = State::from_buffer(frame);
+    let value = T::decode(&mut frame)?;
+    match frame.remaining() {
+        0 => Ok(value),
+        _ => Err(DecodeError::TypeMismatch),
+    }
+}
+
+/// decode a value of type T from `bytes`, which must be consumed completely
+/// return TypeMismatch if bytes remain after the decoded value, see decode_lenient()
+pub fn decode_exact<T: Decode>(bytes: &[u8]) -> DecodeResultT<T> {
+    let mut state = State::from_buffer(bytes.to_vec());
+    let value = T::decode(&mut state)?;
+    match state.remaining() {
+        0 => Ok(value),
+        _ => Err(DecodeError::TypeMismatch),
+    }
+}
+
+/// decode a value of type T from `bytes`, ignoring any bytes after the decoded value
+pub fn decode_lenient<T: Decode>(bytes: &[u8]) -> DecodeResultT<T> {
+    let mut state = State::from_buffer(bytes.to_vec());
+    T::decode(&mut state)
+}
+
+/// decode values of type T from `bytes` until all bytes are consumed, e.g. encoded with encode_seq()
+/// return an error if the last value is truncated or if T does not consume any bytes
+pub fn decode_all<T: Decode>(bytes: &[u8]) -> DecodeResultT<Vec<T>> {
+    let mut state = State::from_buffer(bytes.to_vec());
+    let mut values = Vec::new();
+    while state.remaining() > 0 {
+        let start = state.start;
+        values.push(T::decode(&mut state)?);
+        if state.start == start {
+            return Err(DecodeError::TypeMismatch);
+        }
+    }
+    Ok(values)
+}
+
+/// decode the items written by encode_framed(), skipping items which can not be decoded
+///
+/// Each item is decoded within its length-prefixed region like State::decode_scoped(),
+/// on error the region is skipped and the error is collected instead of aborting.
+/// Decoding stops early if the count or a length prefix is corrupt, since items can not be skipped anymore.
+/// Use (K, V) as T for the entries of a map.
+pub fn decode_lossy<T: Decode>(state: &mut State) -> (Vec<T>, Vec<DecodeError>) {
+    let mut items = Vec::new();
+    let mut errors = Vec::new();
+    let count = match state.read_count(MAX_ARRAY_DECODE_SIZE) {
+        Ok(count) => count,
+        Err(error) => return (items, vec![error]),
+    };
+    for _ in 0..count {
+        let region_start = state.start;
+        match state.decode_scoped::<T>() {
+            Ok(item) => items.push(item),
+            Err(error) => {
+                errors.push(error);
+                state.start = region_start;
+                match state.read_count(usize::MAX) {
+                    Ok(length) => state.start += length,
+                    Err(_) => break,
+                }
+            }
+        }
+    }
+    (items, errors)
+}
+
+/// decode a Vec<T> with at most `max` elements
+/// return ArrayTooLarge if the encoded length exceeds `max`, in addition to MAX_ARRAY_DECODE_SIZE
+pub fn decode_capped<T: Decode>(state: &mut State, max: usize) -> DecodeResultT<Vec<T>> {
+    let length = state.read_count(max)?;
+    let mut vec = Vec::with_capacity(length);
+    if length > 0 {
+        T::decode_extend(state, length, &mut vec)?;
+    }
+    Ok(vec)
+}
+
+//
+// bool
+//
+
+/// compact encoding for bool
+impl Encode for bool {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        state.end += std::mem::size_of::<u8>();
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        // encode true as 1u8 and false as 0u8
+        state.write(&[if *self { 1u8 } else { 0u8 }])
+    }
+}
+
+/// compact decoding for bool
+/// return TypeMismatch for bytes other than 0 and 1
+impl Decode for bool {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        match state.read_next(std::mem::size_of::<u8>())?[0] {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(DecodeError::TypeMismatch),
+        }
+    }
+}
+
+//
+// unsigned integers
+//
+
+/// compact encoding for u8
+impl Encode for u8 {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        if *self <= U8_MAX_VALUE {
+            state.end += std::mem::size_of::<u8>();
+        } else {
+            (*self as u16).pre_encode(state);
+        }
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        if *self <= U8_MAX_VALUE {
+            state.write(&[*self])
+        } else {
+            (*self as u16).encode(state)
+        }
+    }
+
+    /// byte sequences are encoded as raw buffer
+    fn pre_encode_slice(slice: &[Self], state: &mut State) {
+        state.end += slice.len();
+    }
+
+    /// byte sequences are encoded as raw buffer
+    fn encode_slice(slice: &[Self], state: &mut State) -> EncodeResult {
+        state.write(slice)
+    }
+
+    /// byte arrays are encoded as raw fixed bytes without length
+    fn pre_encode_array<const N: usize>(_array: &[Self; N], state: &mut State) {
+        state.end += N;
+    }
+
+    /// byte arrays are encoded as raw fixed bytes without length
+    fn encode_array<const N: usize>(array: &[Self; N], state: &mut State) -> EncodeResult {
+        state.write(array)
+    }
+}
+
+/// compact decoding for u8
+impl Decode for u8 {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        let header = state.peek_u8()?;
+        if header <= U8_MAX_VALUE {
+            let value = state.read_next(std::mem::size_of::<u8>())?[0];
+            Ok(value)
+        } else {
+            u16::decode(state).map(|v| v as u8)
+        }
+    }
+
+    /// byte sequences are decoded from a raw buffer
+    fn decode_extend<C: Extend<Self>>(
+        state: &mut State,
+        length: usize,
+        target: &mut C,
+    ) -> DecodeResultT<()> {
+        target.extend(state.read_next(length)?.iter().copied());
+        Ok(())
+    }
+
+    /// byte arrays are decoded from raw fixed bytes without length
+    fn decode_array<const N: usize>(state: &mut State) -> DecodeResultT<[Self; N]> {
+        Ok(*state.decode_fixed_ref::<N>()?)
+    }
+}
+
+/// compact encoding for u16
+impl Encode for u16 {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        state.end += 1 + (std::mem::size_of::<Self>());
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        state.write(&[U16_PREFIX, *self as u8, (*self >> 8) as u8])
+    }
+}
+
+/// compact decoding for u16
+impl Decode for u16 {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        let buffer = state.read_next(1 + std::mem::size_of::<Self>())?;
+        if buffer[0] == U16_PREFIX {
+            let mut value: Self = buffer[1] as u16;
+            value += buffer[2] as u16 * 256;
+            Ok(value)
+        } else {
+            Err(DecodeError::TypeMismatch)
+        }
+    }
+}
+
+/// compact encoding for u32
+impl Encode for u32 {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        state.end += 1 + (std::mem::size_of::<Self>());
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        state.write(&[U32_PREFIX])?;
+        state.write(&encode_u32(*self))
+    }
+}
+
+/// compact encoding for u32 number to byte array
+#[inline(always)]
+fn encode_u32(value: u32) -> [u8; 4] {
     [
         value as u8,
         (value >> 8) as u8,
@@ -302,8 +1420,8 @@ fn decode_u32(buffer: &[u8]) -> DecodeResultT<u32> {
     }
     let mut value: u32 = buffer[0] as u32;
     value += buffer[1] as u32 * 256;
-    value += buffer[2] as u32 * (256 as u32).pow(2);
-    value += buffer[3] as u32 * (256 as u32).pow(3);
+    value += buffer[2] as u32 * 256_u32.pow(2);
+    value += buffer[3] as u32 * 256_u32.pow(3);
     Ok(value)
 }
 
@@ -331,8 +1449,9 @@ impl Decode for u64 {
     fn decode(state: &mut State) -> DecodeResultT<Self> {
         let buffer = state.read_next(1 + std::mem::size_of::<Self>())?;
         if buffer[0] == U64_PREFIX {
-            let mut value = decode_u32(&buffer[1..6])? as u64;
-            value += decode_u32(&buffer[5..])? as u64 * (2 as u64).pow(32);
+            // lower 32 bits are encoded first, followed by the upper 32 bits
+            let mut value = decode_u32(&buffer[1..5])? as u64;
+            value += decode_u32(&buffer[5..9])? as u64 * 2_u64.pow(32);
             Ok(value)
         } else {
             Err(DecodeError::TypeMismatch)
@@ -341,48 +1460,88 @@ impl Decode for u64 {
 }
 
 // compact encoding for usize
+// encoded like CompactU64, with the smallest possible prefix
 impl Encode for usize {
     /// allocate the required size in State for current type
     fn pre_encode(&self, state: &mut State) {
-        match *self as u128 {
-            x if x <= (U8_MAX_VALUE as u128) => (x as u8).pre_encode(state),
-            x if x <= (u16::MAX as u128) => (x as u16).pre_encode(state),
-            x if x <= (u32::MAX as u128) => (x as u32).pre_encode(state),
-            x if x <= (u64::MAX as u128) => (x as u64).pre_encode(state),
-            _ => unimplemented!(),
-        };
+        CompactU64(*self as u64).pre_encode(state);
     }
 
     /// encode n into state.buffer
     /// requires state.buffer to be allocated first
     fn encode(&self, state: &mut State) -> EncodeResult {
-        match *self as u128 {
-            x if x <= (U8_MAX_VALUE as u128) => (x as u8).encode(state),
-            x if x <= (u16::MAX as u128) => (x as u16).encode(state),
-            x if x <= (u32::MAX as u128) => (x as u32).encode(state),
-            x if x <= (u64::MAX as u128) => (x as u64).encode(state),
-            _ => unimplemented!(),
-        }
+        CompactU64(*self as u64).encode(state)
     }
 }
 
 /// compact decoding for usize
 impl Decode for usize {
     fn decode(state: &mut State) -> DecodeResultT<Self> {
-        let buffer = state.peek_u8()?;
-        match buffer as u16 {
-            x if x <= U8_MAX_VALUE as u16 => u8::decode(state).map(|value| value as usize),
-            x if x <= U16_PREFIX as u16 => u16::decode(state).map(|value| value as usize),
-            x if x <= U32_PREFIX as u16 => u32::decode(state).map(|value| value as usize),
-            x if x <= U64_PREFIX as u16 => u64::decode(state).map(|value| value as usize),
-            _ => unimplemented!(),
-        }
+        CompactU64::decode(state).map(|value| value.0 as usize)
+    }
+}
+
+/// compact encoding for u128
+///
+/// Layout: U128_PREFIX (0xFC) followed by the lower and the upper 64 bits as little-endian u64 each.
+/// Decoding the prefix as any of the fixed width integers u16, u32, u64 (or their signed counterparts)
+/// fails with TypeMismatch. The variable width usize and u8 accept every first byte,
+/// so they read the prefix as the value 252.
+impl Encode for u128 {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        state.end += 1 + (std::mem::size_of::<Self>());
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        state.write(&[U128_PREFIX])?;
+        state.write(&(*self as u64).to_le_bytes())?;
+        state.write(&((*self >> 64) as u64).to_le_bytes())
+    }
+}
+
+/// compact decoding for u128
+impl Decode for u128 {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        let buffer = state.read_next(1 + std::mem::size_of::<Self>())?;
+        if buffer[0] == U128_PREFIX {
+            // lower 64 bits are encoded first, followed by the upper 64 bits
+            let mut bytes = [0; 16];
+            bytes.copy_from_slice(&buffer[1..17]);
+            Ok(Self::from_le_bytes(bytes))
+        } else {
+            Err(DecodeError::TypeMismatch)
+        }
     }
 }
 
 //
-// signed integers
-//
+// signed integers
+//
+
+/// compact encoding for isize
+/// the zig-zag encoded value is encoded like an usize
+impl Encode for isize {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        (zig_zag_encode(*self as i64) as usize).pre_encode(state);
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        (zig_zag_encode(*self as i64) as usize).encode(state)
+    }
+}
+
+/// compact decoding for isize
+impl Decode for isize {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        Ok(zig_zag_decode(usize::decode(state)? as u64) as Self)
+    }
+}
 
 /// compact encoding for i8
 impl Encode for i8 {
@@ -430,404 +1589,1968 @@ impl Decode for i16 {
 impl Encode for i32 {
     /// allocate the required size in State for current type
     fn pre_encode(&self, state: &mut State) {
-        (zig_zag_encode(*self as i64) as u32).pre_encode(state);
+        (zig_zag_encode(*self as i64) as u32).pre_encode(state);
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        (zig_zag_encode(*self as i64) as u32).encode(state)
+    }
+}
+
+/// compact decoding for i32
+impl Decode for i32 {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        Ok(zig_zag_decode(u32::decode(state)? as u64) as Self)
+    }
+}
+
+/// compact encoding for i64
+impl Encode for i64 {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        zig_zag_encode(*self).pre_encode(state);
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        zig_zag_encode(*self).encode(state)
+    }
+}
+
+/// compact decoding for i64
+impl Decode for i64 {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        Ok(zig_zag_decode(u64::decode(state)?))
+    }
+}
+
+/// compact encoding for i128
+/// the zig-zag encoded value is encoded like an u128
+impl Encode for i128 {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        (((*self << 1) ^ (*self >> 127)) as u128).pre_encode(state);
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        (((*self << 1) ^ (*self >> 127)) as u128).encode(state)
+    }
+}
+
+/// compact decoding for i128
+impl Decode for i128 {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        let value = u128::decode(state)?;
+        Ok(((value >> 1) as Self) ^ -((value & 1) as Self))
+    }
+}
+
+//
+// char
+//
+
+/// compact encoding for char
+/// the unicode scalar value is encoded like an usize
+impl Encode for char {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        (*self as usize).pre_encode(state);
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        (*self as usize).encode(state)
+    }
+}
+
+/// compact decoding for char
+/// return an error if the decoded value is not a valid unicode scalar value
+impl Decode for char {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        let value = u32::try_from(usize::decode(state)?).map_err(|_| DecodeError::TypeMismatch)?;
+        char::from_u32(value).ok_or(DecodeError::TypeMismatch)
+    }
+}
+
+//
+// float
+//
+
+/// compact encoding for f32
+/// uses the byte order of the state
+impl Encode for f32 {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        state.end += std::mem::size_of::<Self>();
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        match state.byte_order() {
+            ByteOrder::LittleEndian => state.write(&self.to_le_bytes()),
+            ByteOrder::BigEndian => state.write(&self.to_be_bytes()),
+        }
+    }
+}
+
+/// compact decoding for f32
+/// uses the byte order of the state
+impl Decode for f32 {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        let buffer = *state.decode_fixed_ref()?;
+        match state.byte_order {
+            ByteOrder::LittleEndian => Ok(Self::from_le_bytes(buffer)),
+            ByteOrder::BigEndian => Ok(Self::from_be_bytes(buffer)),
+        }
+    }
+}
+
+/// compact encoding for f64
+/// uses the byte order of the state
+impl Encode for f64 {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        state.end += std::mem::size_of::<Self>();
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        match state.byte_order() {
+            ByteOrder::LittleEndian => state.write(&self.to_le_bytes()),
+            ByteOrder::BigEndian => state.write(&self.to_be_bytes()),
+        }
+    }
+}
+
+/// compact decoding for f64
+/// uses the byte order of the state
+impl Decode for f64 {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        let buffer = *state.decode_fixed_ref()?;
+        match state.byte_order {
+            ByteOrder::LittleEndian => Ok(Self::from_le_bytes(buffer)),
+            ByteOrder::BigEndian => Ok(Self::from_be_bytes(buffer)),
+        }
+    }
+}
+
+//
+// buffers, arrays
+//
+
+/// compact encoding for Option<&[u8]>
+impl Encode for Option<&[u8]> {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        match self {
+            Some(buffer) => {
+                buffer.len().pre_encode(state);
+                state.end += buffer.len();
+            }
+            None => state.end += 1,
+        };
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        match self {
+            Some(buffer) => {
+                buffer.len().encode(state)?;
+                state.write(buffer)
+            }
+            None => state.write(&[0]),
+        }
+    }
+}
+
+/// compact decoding for Option<&[u8]>
+impl Decode for Option<Vec<u8>> {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        let buffer_size = state.read_count(usize::MAX)?;
+        if buffer_size == 0 {
+            return Ok(None);
+        };
+        Ok(Some(Vec::from(state.read_next(buffer_size)?)))
+    }
+}
+
+/// compact decoding for Option<&[u8]>
+/// prefer decoding into Option<Vec<u8>>, which uses the same wire format
+impl Decode for Option<Box<Vec<u8>>> {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        Ok(Option::<Vec<u8>>::decode(state)?.map(Box::new))
+    }
+}
+
+/// wrapper struct for encoding owned buffers with length information
+///
+/// This is the non-optional counterpart to `Option<&[u8]>` and uses the same wire format.
+/// An empty buffer is encoded like `None`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Bytes(pub Vec<u8>);
+
+impl From<Vec<u8>> for Bytes {
+    fn from(buffer: Vec<u8>) -> Self {
+        Self(buffer)
+    }
+}
+
+impl From<Bytes> for Vec<u8> {
+    fn from(bytes: Bytes) -> Self {
+        bytes.0
+    }
+}
+
+/// compact encoding for Bytes
+impl Encode for Bytes {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        self.0.pre_encode(state);
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        self.0.encode(state)
+    }
+}
+
+/// compact decoding for Bytes
+impl Decode for Bytes {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        Vec::<u8>::decode(state).map(Self)
+    }
+}
+
+/// wrapper struct for encoding plain buffers without length information
+#[derive(Debug, PartialEq)]
+pub enum Raw<'a> {
+    /// enum variant which is returned by Decode
+    Vec(Vec<u8>), // TODO: Replace Vec with Box<Vec>?
+    VecRef(&'a Vec<u8>),
+    Slice(&'a [u8]),
+}
+
+/// compact encoding for raw buffer
+impl<'a> Encode for Raw<'a> {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        match *self {
+            Raw::Vec(ref buffer) => state.end += buffer.len(),
+            Raw::VecRef(buffer) => state.end += buffer.len(),
+            Raw::Slice(slice) => state.end += slice.len(),
+        }
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        match *self {
+            Raw::Vec(ref buffer) => state.write(buffer),
+            Raw::VecRef(buffer) => state.write(buffer),
+            Raw::Slice(slice) => state.write(slice),
+        }
+    }
+}
+
+/// compact decoding for raw buffer
+impl<'a> Decode for Raw<'a> {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        if state.end == state.start {
+            Ok(Raw::Vec(vec![]))
+        } else {
+            Ok(Raw::Vec(state.read_remaining()?.into()))
+        }
+    }
+}
+
+/// compact encoding for str, &str is encoded via the blanket implementation for &T
+impl Encode for str {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        // len always returns number of bytes
+        self.len().pre_encode(state);
+        state.end += self.len();
+    }
+
+    /// encode self into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        self.len().encode(state)?;
+        state.write(self.as_bytes())
+    }
+}
+
+/// compact encoding for String
+impl Encode for String {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        self.as_str().pre_encode(state);
+    }
+
+    /// encode self into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        self.as_str().encode(state)
+    }
+}
+
+/// compact decoding into String
+impl Decode for String {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        let mut value = String::new();
+        Self::decode_in_place(state, &mut value)?;
+        Ok(value)
+    }
+}
+
+/// compact decoding into an existing String
+impl DecodeInPlace for String {
+    fn decode_in_place(state: &mut State, target: &mut Self) -> DecodeResultT<()> {
+        let buffer_size =
+            state
+                .read_count(MAX_STRING_DECODE_SIZE)
+                .map_err(|error| match error {
+                    DecodeError::ArrayTooLarge => DecodeError::StringTooLarge,
+                    error => error,
+                })?;
+        target.clear();
+        if buffer_size == 0 {
+            return Ok(());
+        }
+        let buffer_ref = state.read_next(buffer_size)?;
+        /*
+            const s = b.toString(state.buffer, 'utf8', state.start, state.start += len)
+            if (b.byteLength(s) !== len || state.start > state.end) throw new Error('Out of bounds')
+        */
+        target.push_str(std::str::from_utf8(buffer_ref).map_err(|_| DecodeError::InvalidUtf8)?);
+        Ok(())
+    }
+}
+
+/// compact encoding for Path, encoded like a string
+///
+/// The path is converted with Path::to_string_lossy(), so this is lossless for UTF-8 paths only.
+/// Non UTF-8 paths (possible on unix and windows) are encoded with U+FFFD replacement characters.
+/// Path separators are not converted, so paths are only portable between platforms sharing the same separator.
+impl Encode for std::path::Path {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        self.to_string_lossy().as_ref().pre_encode(state);
+    }
+
+    /// encode self into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        self.to_string_lossy().as_ref().encode(state)
+    }
+}
+
+/// compact encoding for PathBuf, see Path for limitations
+impl Encode for std::path::PathBuf {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        self.as_path().pre_encode(state);
+    }
+
+    /// encode self into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        self.as_path().encode(state)
+    }
+}
+
+/// compact decoding into PathBuf from an encoded string
+impl Decode for std::path::PathBuf {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        String::decode(state).map(Self::from)
+    }
+}
+
+/// compact encoding for arrays [T; N]
+///
+/// The array is encoded with Encode::encode_array(): the length N followed by the elements.
+/// Byte arrays [u8; N] differ from this: they are encoded as exactly N raw bytes
+/// without length, like Fixed<N>.
+impl<T, const N: usize> Encode for [T; N]
+where
+    T: Encode,
+{
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        // TODO check for MAX_ARRAY_DECODE_SIZE -> not implemented in JS
+        T::pre_encode_array(self, state);
+    }
+
+    /// encode self into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        T::encode_array(self, state)
+    }
+}
+
+/// compact decoding into arrays [T; N]
+/// return an error if the encoded length does not match N
+/// byte arrays [u8; N] are decoded from N raw bytes without length
+impl<T, const N: usize> Decode for [T; N]
+where
+    T: Decode,
+{
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        T::decode_array(state)
+    }
+}
+
+/// compact encoding for slices [T]
+///
+/// The length is encoded as usize, followed by the elements encoded with Encode::encode_slice().
+impl<T> Encode for [T]
+where
+    T: Encode,
+{
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        self.len().pre_encode(state);
+        // TODO check for MAX_ARRAY_DECODE_SIZE
+        T::pre_encode_slice(self, state);
+    }
+
+    /// encode self into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        self.len().encode(state)?;
+        // TODO check for MAX_ARRAY_DECODE_SIZE
+        T::encode_slice(self, state)
+    }
+}
+
+/// compact encoding for Vec<T>, same layout as [T]
+///
+/// The elements are encoded with Encode::encode_slice().
+/// For Vec<u8> this results in the same layout as the buffer encoding (length + raw bytes)
+/// instead of encoding each byte individually as compact u8.
+impl<T> Encode for Vec<T>
+where
+    T: Encode,
+{
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        self.as_slice().pre_encode(state);
+    }
+
+    /// encode self into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        self.as_slice().encode(state)
+    }
+}
+
+/// compact decoding into Vec<T>
+impl<T> Decode for Vec<T>
+where
+    T: Decode,
+{
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        let mut vec = vec![];
+        Self::decode_in_place(state, &mut vec)?;
+        Ok(vec)
+    }
+}
+
+/// compact decoding into an existing Vec<T>
+impl<T> DecodeInPlace for Vec<T>
+where
+    T: Decode,
+{
+    fn decode_in_place(state: &mut State, target: &mut Self) -> DecodeResultT<()> {
+        // the element limit is checked by decode_extend(), byte buffers are not limited
+        let buffer_size = if std::mem::size_of::<T>() == 0 {
+            // zero-sized elements like () may not consume any bytes,
+            // so the count is not limited by the remaining bytes
+            usize::decode(state)?
+        } else {
+            state.read_count(usize::MAX)?
+        };
+        target.clear();
+        if buffer_size == 0 {
+            return Ok(());
+        }
+        target.reserve(buffer_size);
+        T::decode_extend(state, buffer_size, target)
+    }
+}
+
+/// compact encoding for boxed slices Box<[T]>, same layout as Vec<T>
+impl<T> Encode for Box<[T]>
+where
+    T: Encode,
+{
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        self.as_ref().pre_encode(state);
+    }
+
+    /// encode self into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        self.as_ref().encode(state)
+    }
+}
+
+/// compact decoding into boxed slices Box<[T]>, decoded like Vec<T>
+impl<T> Decode for Box<[T]>
+where
+    T: Decode,
+{
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        Vec::<T>::decode(state).map(Vec::into_boxed_slice)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum U32Array<'a> {
+    Vec(Vec<u32>),
+    VecRef(&'a Vec<u32>),
+    Slice(&'a [u32]),
+}
+
+/// compact encoding for U32Array
+/// MDN: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Uint32Array
+impl Encode for U32Array<'_> {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        let vec = match self {
+            U32Array::Vec(vec) => vec.as_slice(),
+            U32Array::VecRef(vec) => vec.as_slice(),
+            U32Array::Slice(slice) => slice,
+        };
+        vec.len().pre_encode(state);
+        // u32 has 4 bytes length
+        state.end += vec.len() * 4;
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        let vec = match self {
+            U32Array::Vec(vec) => vec.as_slice(),
+            U32Array::VecRef(vec) => vec.as_slice(),
+            U32Array::Slice(slice) => slice,
+        };
+        vec.len().encode(state)?;
+        let native_order = match state.byte_order() {
+            ByteOrder::LittleEndian => cfg!(target_endian = "little"),
+            ByteOrder::BigEndian => cfg!(target_endian = "big"),
+        };
+        if native_order {
+            encode_u32_bulk(vec, state)
+        } else {
+            encode_u32_each(vec, state)
+        }
+    }
+}
+
+/// write all values with a single write, requires the state byte order to match the host
+#[inline(always)]
+fn encode_u32_bulk(values: &[u32], state: &mut State) -> EncodeResult {
+    state.write(bytemuck::cast_slice(values))
+}
+
+/// write each value separately using the byte order of the state
+#[inline(always)]
+fn encode_u32_each(values: &[u32], state: &mut State) -> EncodeResult {
+    for num in values {
+        match state.byte_order() {
+            ByteOrder::LittleEndian => state.write(&num.to_le_bytes())?,
+            ByteOrder::BigEndian => state.write(&num.to_be_bytes())?,
+        }
+    }
+    Ok(())
+}
+
+/// compact decoding for U32Array
+/// returns U32Array::Vec(_)
+impl Decode for U32Array<'_> {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        let buffer_size = state.read_count(MAX_ARRAY_DECODE_SIZE)?;
+        if buffer_size == 0 {
+            return Ok(U32Array::Vec(vec![]));
+        };
+        /* JS Implementation contains this part as well
+         * TODO: clarify functionality with original author
+            // const byteOffset = state.buffer.byteOffset + state.start
+            // const s = state.start
+
+            // state.start += len * 4
+
+            // if ((byteOffset & 3) === 0) {
+            //   const arr = new Uint32Array(state.buffer.buffer, byteOffset, len)
+            //   if (BE) LEToHost32(arr, len)
+            //   return arr
+            // }
+        */
+        // align mismatch
+        let mut vec: Vec<u32> = Vec::with_capacity(buffer_size);
+        // read all u32 values and decode them using the byte order of the state
+        // difference to JS implementation: decode each value instead of reading buffer and then decoding buffer
+        for _ in 1..(buffer_size + 1) {
+            let buffer = *state.decode_fixed_ref()?;
+            vec.push(match state.byte_order {
+                ByteOrder::LittleEndian => u32::from_le_bytes(buffer),
+                ByteOrder::BigEndian => u32::from_be_bytes(buffer),
+            });
+        }
+        Ok(U32Array::Vec(vec))
+    }
+}
+
+/// wrapper struct for encoding booleans as packed bits
+///
+/// The number of elements is encoded as usize, followed by ceil(n / 8) bytes.
+/// Element i is stored in bit (i % 8) of byte (i / 8), unused bits are zero.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackedBools(pub Vec<bool>);
+
+/// compact encoding for PackedBools
+impl Encode for PackedBools {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        self.0.len().pre_encode(state);
+        state.end += self.0.len().div_ceil(8);
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        self.0.len().encode(state)?;
+        let bytes: Vec<u8> = self
+            .0
+            .chunks(8)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .enumerate()
+                    .fold(0u8, |byte, (bit, value)| byte | ((*value as u8) << bit))
+            })
+            .collect();
+        state.write(&bytes)
+    }
+}
+
+/// compact decoding for PackedBools
+/// return an error if unused bits of the last byte are set
+impl Decode for PackedBools {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        let count = usize::decode(state)?;
+        if count == 0 {
+            return Ok(Self(vec![]));
+        } else if count.div_ceil(8) > state.remaining() {
+            return Err(DecodeError::InvalidLength {
+                declared: count.div_ceil(8),
+                available: state.remaining(),
+            });
+        }
+        let bytes = state.read_next(count.div_ceil(8))?;
+        let unused_bits = bytes.len() * 8 - count;
+        if unused_bits > 0 && bytes[bytes.len() - 1] >> (8 - unused_bits) != 0 {
+            return Err(DecodeError::TypeMismatch);
+        }
+        Ok(Self(
+            (0..count)
+                .map(|i| bytes[i / 8] & (1 << (i % 8)) != 0)
+                .collect(),
+        ))
+    }
+}
+
+/// wrapper struct for encoding sparse sequences of optional values
+///
+/// The total number of elements is encoded as usize, followed by the number of Some entries
+/// and an (index, value) pair for each of them, with strictly increasing indices.
+/// The total number of elements is limited to MAX_ARRAY_DECODE_SIZE when decoding.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Sparse<T>(pub Vec<Option<T>>);
+
+impl<T> Sparse<T> {
+    fn entries(&self) -> impl Iterator<Item = (usize, &T)> {
+        self.0
+            .iter()
+            .enumerate()
+            .filter_map(|(index, value)| value.as_ref().map(|value| (index, value)))
+    }
+}
+
+/// compact encoding for Sparse<T>
+impl<T: Encode> Encode for Sparse<T> {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        self.0.len().pre_encode(state);
+        self.entries().count().pre_encode(state);
+        for (index, value) in self.entries() {
+            index.pre_encode(state);
+            value.pre_encode(state);
+        }
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        self.0.len().encode(state)?;
+        self.entries().count().encode(state)?;
+        for (index, value) in self.entries() {
+            index.encode(state)?;
+            value.encode(state)?;
+        }
+        Ok(())
+    }
+}
+
+/// compact decoding for Sparse<T>
+/// return an error if the indices are out of range or not strictly increasing
+impl<T: Decode> Decode for Sparse<T> {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        let length = usize::decode(state)?;
+        if length > MAX_ARRAY_DECODE_SIZE {
+            return Err(DecodeError::ArrayTooLarge);
+        }
+        let count = state.read_count(length)?;
+        let mut vec: Vec<Option<T>> = Vec::with_capacity(length);
+        for _ in 0..count {
+            let index = usize::decode(state)?;
+            if index < vec.len() || index >= length {
+                return Err(DecodeError::TypeMismatch);
+            }
+            vec.resize_with(index, || None);
+            vec.push(Some(T::decode(state)?));
+        }
+        vec.resize_with(length, || None);
+        Ok(Self(vec))
+    }
+}
+
+/// wrapper struct for encoding nullable columns
+///
+/// The presence of each element is encoded as PackedBools, followed by the Some values
+/// without any further length or discriminant.
+/// Use Sparse<T> instead if most of the elements are None.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Column<T>(pub Vec<Option<T>>);
+
+impl<T> Column<T> {
+    fn presence(&self) -> PackedBools {
+        PackedBools(self.0.iter().map(Option::is_some).collect())
+    }
+}
+
+/// compact encoding for Column<T>
+impl<T: Encode> Encode for Column<T> {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        self.presence().pre_encode(state);
+        for value in self.0.iter().flatten() {
+            value.pre_encode(state);
+        }
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        self.presence().encode(state)?;
+        for value in self.0.iter().flatten() {
+            value.encode(state)?;
+        }
+        Ok(())
+    }
+}
+
+/// compact decoding for Column<T>
+impl<T: Decode> Decode for Column<T> {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        let presence = PackedBools::decode(state)?;
+        if presence.0.len() > MAX_ARRAY_DECODE_SIZE {
+            return Err(DecodeError::ArrayTooLarge);
+        }
+        presence
+            .0
+            .into_iter()
+            .map(|present| present.then(|| T::decode(state)).transpose())
+            .collect::<DecodeResultT<_>>()
+            .map(Self)
+    }
+}
+
+/// compact encoding for arrays matching `c.array(enc)` of the JS compact-encoding library
+///
+/// The number of elements is encoded as compact uint (like usize), followed by each element.
+/// In contrast to Vec<T>, elements are always encoded one by one, so JsArray<u8> is not a raw buffer.
+/// Use usize elements for `c.uint` and String elements for `c.string`.
+/// The number of elements is limited to MAX_ARRAY_DECODE_SIZE when decoding.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JsArray<T>(pub Vec<T>);
+
+/// compact encoding for JsArray<T>
+impl<T: Encode> Encode for JsArray<T> {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        self.0.len().pre_encode(state);
+        for value in &self.0 {
+            value.pre_encode(state);
+        }
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        self.0.len().encode(state)?;
+        for value in &self.0 {
+            value.encode(state)?;
+        }
+        Ok(())
+    }
+}
+
+/// compact decoding for JsArray<T>
+impl<T: Decode> Decode for JsArray<T> {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        let length = state.read_count(MAX_ARRAY_DECODE_SIZE)?;
+        let mut vec = Vec::with_capacity(length);
+        for _ in 0..length {
+            vec.push(T::decode(state)?);
+        }
+        Ok(Self(vec))
+    }
+}
+
+/// wrapper struct for encoding homogeneous batches of records
+///
+/// The number of elements is encoded once, followed by the elements without any per-element type tag.
+/// This uses the same wire format as Vec<T> and is more compact than a list of `dyn EncodeDyn`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Batch<T>(pub Vec<T>);
+
+impl<T> Batch<T> {
+    /// create a new batch from a list of records
+    pub fn new(records: Vec<T>) -> Self {
+        Self(records)
+    }
+
+    /// return the list of records
+    pub fn into_inner(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<T> From<Batch<T>> for Vec<T> {
+    fn from(batch: Batch<T>) -> Self {
+        batch.0
+    }
+}
+
+/// compact encoding for Batch<T>
+impl<T: Encode> Encode for Batch<T> {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        self.0.pre_encode(state);
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        self.0.encode(state)
+    }
+}
+
+/// compact decoding for Batch<T>
+impl<T: Decode> Decode for Batch<T> {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        Vec::<T>::decode(state).map(Self)
+    }
+}
+
+/// vector of f32 values quantized to i8, e.g. for embeddings
+///
+/// The scale is encoded as f32, followed by the quantized values as buffer of raw bytes.
+/// Each value is approximated by `value * scale`, the error is at most `scale / 2`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QuantizedVec {
+    pub scale: f32,
+    pub values: Vec<i8>,
+}
+
+impl QuantizedVec {
+    /// quantize `values`, the largest absolute value is mapped to 127
+    pub fn from_f32_slice(values: &[f32]) -> Self {
+        let max = values.iter().fold(0f32, |max, value| max.max(value.abs()));
+        let scale = max / i8::MAX as f32;
+        let values = values
+            .iter()
+            .map(|value| match scale > 0.0 {
+                true => (value / scale).round().clamp(-127.0, 127.0) as i8,
+                false => 0,
+            })
+            .collect();
+        Self { scale, values }
+    }
+
+    /// return the approximated f32 values
+    pub fn to_f32_vec(&self) -> Vec<f32> {
+        self.values
+            .iter()
+            .map(|value| *value as f32 * self.scale)
+            .collect()
+    }
+}
+
+/// compact encoding for QuantizedVec
+impl Encode for QuantizedVec {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        self.scale.pre_encode(state);
+        self.values.len().pre_encode(state);
+        state.end += self.values.len();
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        self.scale.encode(state)?;
+        let bytes: &[u8] = bytemuck::cast_slice(&self.values);
+        bytes.encode(state)
+    }
+}
+
+/// compact decoding for QuantizedVec
+impl Decode for QuantizedVec {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        let scale = f32::decode(state)?;
+        let length = state.read_count(MAX_ARRAY_DECODE_SIZE)?;
+        let values = bytemuck::cast_slice(state.read_next(length)?).to_vec();
+        Ok(Self { scale, values })
+    }
+}
+
+/// compact encoding for fixed size buffers
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd)]
+pub struct Fixed<const N: usize>([u8; N]);
+
+pub type Fixed32 = Fixed<32>;
+pub type Fixed64 = Fixed<64>;
+
+impl<const N: usize> Fixed<N> {
+    /// return a reference to the wrapped bytes
+    pub fn as_bytes(&self) -> &[u8; N] {
+        &self.0
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for Fixed<N> {
+    fn from(bytes: [u8; N]) -> Self {
+        Self(bytes)
+    }
+}
+
+/// compact encoding for Fixed<N>
+impl<const N: usize> Encode for Fixed<N> {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        state.end += N;
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        state.write(&self.0[..])
+    }
+}
+
+/// compact decoding for Fixed<N>
+impl<const N: usize> Decode for Fixed<N> {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        let buffer_ref = state.read_next(N)?;
+        if buffer_ref.len() != N {
+            return Err(DecodeError::BufferTooSmall);
+        }
+        let mut fixed = Self([0; N]);
+        fixed.0.copy_from_slice(buffer_ref);
+        Ok(fixed)
+    }
+}
+
+/// compact encoding for fixed size buffers, where the size is only known at runtime
+///
+/// The buffer is encoded without length information, so the size has to be known for decoding.
+/// Use FixedDyn::decode_len() to decode a buffer of a given size.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixedDyn(pub Box<[u8]>);
+
+impl FixedDyn {
+    /// return the number of bytes in the buffer
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// return true if the buffer is empty
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// decode a buffer of `length` bytes
+    pub fn decode_len(state: &mut State, length: usize) -> DecodeResultT<Self> {
+        if length == 0 {
+            return Ok(Self(Box::new([])));
+        }
+        Ok(Self(state.read_next(length)?.into()))
+    }
+}
+
+impl From<Vec<u8>> for FixedDyn {
+    fn from(buffer: Vec<u8>) -> Self {
+        Self(buffer.into_boxed_slice())
+    }
+}
+
+/// compact encoding for FixedDyn
+impl Encode for FixedDyn {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        state.end += self.0.len();
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        state.write(&self.0)
+    }
+}
+
+/// fixed width encoding for u32, always 4 bytes without prefix (little-endian by default)
+///
+/// Use this instead of the compact u32 encoding for fixed-layout records.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FixedU32(pub u32);
+
+/// fixed width encoding for FixedU32
+impl Encode for FixedU32 {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        state.end += std::mem::size_of::<u32>();
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        match state.byte_order() {
+            ByteOrder::LittleEndian => state.write(&self.0.to_le_bytes()),
+            ByteOrder::BigEndian => state.write(&self.0.to_be_bytes()),
+        }
+    }
+}
+
+/// fixed width decoding for FixedU32
+impl Decode for FixedU32 {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        let buffer = *state.decode_fixed_ref()?;
+        match state.byte_order {
+            ByteOrder::LittleEndian => Ok(Self(u32::from_le_bytes(buffer))),
+            ByteOrder::BigEndian => Ok(Self(u32::from_be_bytes(buffer))),
+        }
+    }
+}
+
+/// fixed width encoding for u64, always 8 bytes without prefix (little-endian by default)
+///
+/// Use this instead of the compact u64 encoding for fixed-layout records, e.g. timestamps.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FixedU64(pub u64);
+
+/// fixed width encoding for FixedU64
+impl Encode for FixedU64 {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        state.end += std::mem::size_of::<u64>();
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        match state.byte_order() {
+            ByteOrder::LittleEndian => state.write(&self.0.to_le_bytes()),
+            ByteOrder::BigEndian => state.write(&self.0.to_be_bytes()),
+        }
+    }
+}
+
+/// fixed width decoding for FixedU64
+impl Decode for FixedU64 {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        let buffer = *state.decode_fixed_ref()?;
+        match state.byte_order {
+            ByteOrder::LittleEndian => Ok(Self(u64::from_le_bytes(buffer))),
+            ByteOrder::BigEndian => Ok(Self(u64::from_be_bytes(buffer))),
+        }
+    }
+}
+
+/// encoding for integers in the range MIN..=MAX
+///
+/// The value is encoded as `value - MIN` in little-endian byte order,
+/// using the minimum number of bytes required for `MAX - MIN` and no prefix,
+/// e.g. Bounded<0, 1000> always occupies 2 bytes and Bounded<0, 255> 1 byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Bounded<const MIN: i64, const MAX: i64>(i64);
+
+impl<const MIN: i64, const MAX: i64> Bounded<MIN, MAX> {
+    /// number of bytes used for encoding
+    pub const WIDTH: usize = {
+        assert!(MIN <= MAX, "MIN must not be larger than MAX");
+        let range = MAX.abs_diff(MIN);
+        (u64::BITS - range.leading_zeros()).div_ceil(8) as usize
+    };
+
+    /// return None if `value` is outside of MIN..=MAX
+    pub fn new(value: i64) -> Option<Self> {
+        if (MIN..=MAX).contains(&value) {
+            Some(Self(value))
+        } else {
+            None
+        }
+    }
+
+    /// return the wrapped value
+    pub fn get(&self) -> i64 {
+        self.0
+    }
+}
+
+/// encoding for Bounded<MIN, MAX>
+impl<const MIN: i64, const MAX: i64> Encode for Bounded<MIN, MAX> {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        state.end += Self::WIDTH;
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        state.write(&self.0.abs_diff(MIN).to_le_bytes()[..Self::WIDTH])
+    }
+}
+
+/// decoding for Bounded<MIN, MAX>
+/// return an error if the decoded value is outside of MIN..=MAX
+impl<const MIN: i64, const MAX: i64> Decode for Bounded<MIN, MAX> {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        let mut offset_bytes = [0u8; 8];
+        if Self::WIDTH > 0 {
+            offset_bytes[..Self::WIDTH].copy_from_slice(state.read_next(Self::WIDTH)?);
+        }
+        let offset = u64::from_le_bytes(offset_bytes);
+        if offset > MAX.abs_diff(MIN) {
+            return Err(DecodeError::TypeMismatch);
+        }
+        Ok(Self(MIN.wrapping_add_unsigned(offset)))
+    }
+}
+
+/// set of up to 64 flags stored as bitmask
+///
+/// The bitmask is encoded as compact integer with the smallest possible prefix,
+/// e.g. bitmasks up to 0xFC occupy a single byte.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Flags64(pub u64);
+
+impl Flags64 {
+    /// return true if flag `bit` (0..64) is set
+    pub fn get(&self, bit: u32) -> bool {
+        bit < u64::BITS && self.0 & (1 << bit) != 0
+    }
+
+    /// set flag `bit` (0..64) to `value`
+    pub fn set(&mut self, bit: u32, value: bool) {
+        assert!(bit < u64::BITS, "flag {bit} out of range");
+        if value {
+            self.0 |= 1 << bit;
+        } else {
+            self.0 &= !(1 << bit);
+        }
+    }
+
+    /// set flag `bit` (0..64)
+    pub fn insert(&mut self, bit: u32) {
+        self.set(bit, true);
+    }
+
+    /// clear flag `bit` (0..64)
+    pub fn remove(&mut self, bit: u32) {
+        self.set(bit, false);
+    }
+}
+
+/// compact encoding for Flags64
+impl Encode for Flags64 {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        CompactU64(self.0).pre_encode(state);
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        CompactU64(self.0).encode(state)
+    }
+}
+
+/// compact decoding for Flags64
+impl Decode for Flags64 {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        CompactU64::decode(state).map(|value| Self(value.0))
+    }
+}
+
+/// u64 encoded with the smallest possible prefix, independent of the platform
+/// this is the compact uint encoding used by usize
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CompactU64(pub u64);
+
+/// compact encoding for CompactU64
+impl Encode for CompactU64 {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        match self.0 {
+            x if x <= U8_MAX_VALUE as u64 => (x as u8).pre_encode(state),
+            x if x <= u16::MAX as u64 => (x as u16).pre_encode(state),
+            x if x <= u32::MAX as u64 => (x as u32).pre_encode(state),
+            x => x.pre_encode(state),
+        }
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        match self.0 {
+            x if x <= U8_MAX_VALUE as u64 => (x as u8).encode(state),
+            x if x <= u16::MAX as u64 => (x as u16).encode(state),
+            x if x <= u32::MAX as u64 => (x as u32).encode(state),
+            x => x.encode(state),
+        }
+    }
+}
+
+/// compact decoding for CompactU64
+impl Decode for CompactU64 {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        let value = match state.peek_u8()? {
+            x if x <= U8_MAX_VALUE => u8::decode(state)? as u64,
+            U16_PREFIX => u16::decode(state)? as u64,
+            U32_PREFIX => u32::decode(state)? as u64,
+            _ => u64::decode(state)?,
+        };
+        Ok(Self(value))
+    }
+}
+
+/// wrapper struct for encoding buffers with a fixed width length prefix
+///
+/// The length is encoded as little-endian integer with `WIDTH` bytes (1 to 8),
+/// instead of the compact usize encoding used for regular buffers.
+#[derive(Debug, PartialEq)]
+pub struct LengthPrefixed<T, const WIDTH: usize>(pub T);
+
+/// compact encoding for LengthPrefixed<T, WIDTH>
+impl<T: AsRef<[u8]>, const WIDTH: usize> Encode for LengthPrefixed<T, WIDTH> {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        state.end += WIDTH + self.0.as_ref().len();
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        const { assert!(WIDTH >= 1 && WIDTH <= 8, "WIDTH must be between 1 and 8") };
+        let buffer = self.0.as_ref();
+        let length = buffer.len() as u64;
+        if WIDTH < 8 && length >= 1 << (8 * WIDTH) {
+            return Err(EncodeError::LengthTooLarge);
+        }
+        state.write(&length.to_le_bytes()[..WIDTH])?;
+        state.write(buffer)
+    }
+}
+
+/// compact decoding for LengthPrefixed<Vec<u8>, WIDTH>
+impl<const WIDTH: usize> Decode for LengthPrefixed<Vec<u8>, WIDTH> {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        const { assert!(WIDTH >= 1 && WIDTH <= 8, "WIDTH must be between 1 and 8") };
+        let mut length_bytes = [0u8; 8];
+        length_bytes[..WIDTH].copy_from_slice(state.read_next(WIDTH)?);
+        let length = u64::from_le_bytes(length_bytes);
+        if length > state.remaining() as u64 {
+            return Err(DecodeError::InvalidLength {
+                declared: usize::try_from(length).unwrap_or(usize::MAX),
+                available: state.remaining(),
+            });
+        } else if length == 0 {
+            return Ok(Self(vec![]));
+        }
+        Ok(Self(state.read_next(length as usize)?.into()))
+    }
+}
+
+//
+// maps
+//
+
+/// compact encoding for HashMap<K, V>
+///
+/// The number of entries is encoded as usize, followed by each key and value.
+/// Entries are sorted by key to get a deterministic encoding,
+/// which requires the keys to implement Ord.
+impl<K, V, S> Encode for HashMap<K, V, S>
+where
+    K: Encode + Ord,
+    V: Encode,
+{
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        self.len().pre_encode(state);
+        for (key, value) in self.iter() {
+            key.pre_encode(state);
+            value.pre_encode(state);
+        }
+    }
+
+    /// encode self into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        let mut entries: Vec<(&K, &V)> = self.iter().collect();
+        entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+        self.len().encode(state)?;
+        for (key, value) in entries {
+            key.encode(state)?;
+            value.encode(state)?;
+        }
+        Ok(())
+    }
+}
+
+/// compact decoding into HashMap<K, V>
+/// return an error if a key is contained more than once
+impl<K, V, S> Decode for HashMap<K, V, S>
+where
+    K: Decode + Eq + Hash,
+    V: Decode,
+    S: BuildHasher + Default,
+{
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        let count = state.read_count(MAX_ARRAY_DECODE_SIZE)?;
+        let mut map = HashMap::with_capacity_and_hasher(count, S::default());
+        for _ in 0..count {
+            let key = K::decode(state)?;
+            let value = V::decode(state)?;
+            if map.insert(key, value).is_some() {
+                return Err(DecodeError::TypeMismatch);
+            }
+        }
+        Ok(map)
+    }
+}
+
+/// compact encoding for BTreeMap<K, V>
+///
+/// The number of entries is encoded as usize, followed by each key and value in key order.
+/// This is the same layout as HashMap<K, V>.
+impl<K, V> Encode for BTreeMap<K, V>
+where
+    K: Encode,
+    V: Encode,
+{
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        self.len().pre_encode(state);
+        for (key, value) in self.iter() {
+            key.pre_encode(state);
+            value.pre_encode(state);
+        }
+    }
+
+    /// encode self into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        self.len().encode(state)?;
+        for (key, value) in self.iter() {
+            key.encode(state)?;
+            value.encode(state)?;
+        }
+        Ok(())
+    }
+}
+
+/// compact decoding into BTreeMap<K, V>
+/// return an error if a key is contained more than once
+impl<K, V> Decode for BTreeMap<K, V>
+where
+    K: Decode + Ord,
+    V: Decode,
+{
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        let count = state.read_count(MAX_ARRAY_DECODE_SIZE)?;
+        let mut map = BTreeMap::new();
+        for _ in 0..count {
+            let key = K::decode(state)?;
+            let value = V::decode(state)?;
+            if map.insert(key, value).is_some() {
+                return Err(DecodeError::TypeMismatch);
+            }
+        }
+        Ok(map)
+    }
+}
+
+//
+// option
+//
+
+/// wrapper struct for encoding optional values of any type
+///
+/// A tag byte (0 for None, 1 for Some) is followed by the encoded value if present.
+/// There is no generic implementation for Option<T>, as `Option<&[u8]>` is encoded like `c.buffer`
+/// of the JS library, with a length prefix and without a tag byte.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Nullable<T>(pub Option<T>);
+
+impl<T> From<Option<T>> for Nullable<T> {
+    fn from(value: Option<T>) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> From<Nullable<T>> for Option<T> {
+    fn from(value: Nullable<T>) -> Self {
+        value.0
+    }
+}
+
+/// compact encoding for Nullable<T>
+impl<T: Encode> Encode for Nullable<T> {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        state.end += 1;
+        if let Some(value) = &self.0 {
+            value.pre_encode(state);
+        }
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        match &self.0 {
+            Some(value) => {
+                state.write(&[1])?;
+                value.encode(state)
+            }
+            None => state.write(&[0]),
+        }
+    }
+}
+
+/// compact decoding for Nullable<T>
+/// return an error if the tag byte is neither 0 nor 1
+impl<T: Decode> Decode for Nullable<T> {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        match state.read_next(1)?[0] {
+            0 => Ok(Self(None)),
+            1 => Ok(Self(Some(T::decode(state)?))),
+            _ => Err(DecodeError::TypeMismatch),
+        }
+    }
+}
+
+//
+// result
+//
+
+/// compact encoding for Result<T, E>
+/// a tag byte (0 for Ok, 1 for Err) is followed by the encoded value
+impl<T: Encode, E: Encode> Encode for Result<T, E> {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        state.end += 1;
+        match self {
+            Ok(value) => value.pre_encode(state),
+            Err(error) => error.pre_encode(state),
+        }
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        match self {
+            Ok(value) => {
+                state.write(&[0])?;
+                value.encode(state)
+            }
+            Err(error) => {
+                state.write(&[1])?;
+                error.encode(state)
+            }
+        }
+    }
+}
+
+/// compact decoding for Result<T, E>
+/// return an error if the tag byte is neither 0 nor 1
+impl<T: Decode, E: Decode> Decode for Result<T, E> {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        match state.read_next(1)?[0] {
+            0 => Ok(Ok(T::decode(state)?)),
+            1 => Ok(Err(E::decode(state)?)),
+            _ => Err(DecodeError::TypeMismatch),
+        }
+    }
+}
+
+/// compact encoding for Infallible
+/// no value of this type exists, so encoding can never be called
+impl Encode for std::convert::Infallible {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, _state: &mut State) {
+        match *self {}
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, _state: &mut State) -> EncodeResult {
+        match *self {}
+    }
+}
+
+/// compact decoding for Infallible
+/// always returns an error, as no value of this type can be encoded
+impl Decode for std::convert::Infallible {
+    fn decode(_state: &mut State) -> DecodeResultT<Self> {
+        Err(DecodeError::TypeMismatch)
+    }
+}
+
+//
+// tuples
+//
+
+/// compact encoding for the unit type, nothing is encoded
+impl Encode for () {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, _state: &mut State) {}
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, _state: &mut State) -> EncodeResult {
+        Ok(())
+    }
+}
+
+/// compact decoding for the unit type, nothing is decoded
+impl Decode for () {
+    fn decode(_state: &mut State) -> DecodeResultT<Self> {
+        Ok(())
+    }
+}
+
+/// compact encoding for single element tuples, encoded exactly like A
+impl<A: Encode> Encode for (A,) {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        self.0.pre_encode(state);
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        self.0.encode(state)
+    }
+}
+
+/// compact decoding for single element tuples
+impl<A: Decode> Decode for (A,) {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        Ok((A::decode(state)?,))
+    }
+}
+
+/// implement Encode and Decode for tuples with the given element types and indices
+/// the elements are encoded in order without prefix, as the arity is known from the type
+macro_rules! impl_tuple {
+    ($($name:ident $index:tt),+) => {
+        /// compact encoding for tuples, the elements are encoded in order without prefix
+        impl<$($name: Encode),+> Encode for ($($name,)+) {
+            /// allocate the required size in State for current type
+            fn pre_encode(&self, state: &mut State) {
+                $(self.$index.pre_encode(state);)+
+            }
+
+            /// encode n into state.buffer
+            /// requires state.buffer to be allocated first
+            fn encode(&self, state: &mut State) -> EncodeResult {
+                $(self.$index.encode(state)?;)+
+                Ok(())
+            }
+        }
+
+        /// compact decoding for tuples
+        impl<$($name: Decode),+> Decode for ($($name,)+) {
+            fn decode(state: &mut State) -> DecodeResultT<Self> {
+                Ok(($($name::decode(state)?,)+))
+            }
+        }
+    };
+}
+
+impl_tuple!(A 0, B 1);
+impl_tuple!(A 0, B 1, C 2);
+impl_tuple!(A 0, B 1, C 2, D 3);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11);
+
+//
+// shared ownership wrappers
+//
+
+/// compact encoding for references &T, the referenced value is encoded
+impl<T: Encode + ?Sized> Encode for &T {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        (**self).pre_encode(state);
     }
 
     /// encode n into state.buffer
     /// requires state.buffer to be allocated first
     fn encode(&self, state: &mut State) -> EncodeResult {
-        (zig_zag_encode(*self as i64) as u32).encode(state)
-    }
-}
-
-/// compact decoding for i32
-impl Decode for i32 {
-    fn decode(state: &mut State) -> DecodeResultT<Self> {
-        Ok(zig_zag_decode(u32::decode(state)? as u64) as Self)
+        (**self).encode(state)
     }
 }
 
-/// compact encoding for i64
-impl Encode for i64 {
+/// compact encoding for Arc<T>
+/// the inner value is encoded, shared references are not deduplicated
+impl<T: Encode> Encode for Arc<T> {
     /// allocate the required size in State for current type
     fn pre_encode(&self, state: &mut State) {
-        (zig_zag_encode(*self) as u64).pre_encode(state);
+        self.as_ref().pre_encode(state);
     }
 
     /// encode n into state.buffer
     /// requires state.buffer to be allocated first
     fn encode(&self, state: &mut State) -> EncodeResult {
-        (zig_zag_encode(*self) as u64).encode(state)
+        self.as_ref().encode(state)
     }
 }
 
-/// compact decoding for i64
-impl Decode for i64 {
+/// compact decoding for Arc<T>
+/// the decoded value is wrapped in a new Arc
+impl<T: Decode> Decode for Arc<T> {
     fn decode(state: &mut State) -> DecodeResultT<Self> {
-        Ok(zig_zag_decode(u32::decode(state)? as u64) as Self)
+        T::decode(state).map(Arc::new)
     }
 }
 
-//
-// float
-//
-
-/// compact encoding for f32
-impl Encode for f32 {
+/// compact encoding for Rc<T>
+/// the inner value is encoded, shared references are not deduplicated
+impl<T: Encode> Encode for Rc<T> {
     /// allocate the required size in State for current type
     fn pre_encode(&self, state: &mut State) {
-        state.end += std::mem::size_of::<Self>();
+        self.as_ref().pre_encode(state);
     }
 
     /// encode n into state.buffer
     /// requires state.buffer to be allocated first
     fn encode(&self, state: &mut State) -> EncodeResult {
-        state.write(&self.to_le_bytes())
+        self.as_ref().encode(state)
     }
 }
 
-/// compact decoding for f32
-impl Decode for f32 {
+/// compact decoding for Rc<T>
+/// the decoded value is wrapped in a new Rc
+impl<T: Decode> Decode for Rc<T> {
     fn decode(state: &mut State) -> DecodeResultT<Self> {
-        let buffer = state.read_next(std::mem::size_of::<Self>())?;
-        let value: Self =
-            Self::from_le_bytes(buffer.try_into().map_err(|_| DecodeError::BufferTooSmall)?);
-        Ok(value)
+        T::decode(state).map(Rc::new)
     }
 }
 
-/// compact encoding for f64
-impl Encode for f64 {
-    /// allocate the required size in State for current type
-    fn pre_encode(&self, state: &mut State) {
-        state.end += std::mem::size_of::<Self>();
+//
+// trait objects
+//
+
+/// stable id of a concrete type which is encoded in front of it as part of a trait object
+pub trait TypeTag {
+    const TYPE_ID: u32;
+}
+
+/// object safe counterpart of [Encode] for encoding trait objects
+///
+/// Implemented for all types which implement Encode and TypeTag.
+/// Use it as supertrait of the trait used in `Box<dyn Trait>`.
+pub trait EncodeDyn {
+    /// return TypeTag::TYPE_ID of the concrete type
+    fn dyn_type_id(&self) -> u32;
+
+    /// allocate the required size in State for the concrete type, without the type id
+    fn pre_encode_dyn(&self, state: &mut State);
+
+    /// encode the concrete type into state.buffer, without the type id
+    fn encode_dyn(&self, state: &mut State) -> EncodeResult;
+}
+
+impl<T: Encode + TypeTag> EncodeDyn for T {
+    fn dyn_type_id(&self) -> u32 {
+        T::TYPE_ID
     }
 
-    /// encode n into state.buffer
-    /// requires state.buffer to be allocated first
-    fn encode(&self, state: &mut State) -> EncodeResult {
-        state.write(&self.to_le_bytes())
+    fn pre_encode_dyn(&self, state: &mut State) {
+        self.pre_encode(state);
     }
-}
 
-/// compact decoding for f64
-impl Decode for f64 {
-    fn decode(state: &mut State) -> DecodeResultT<Self> {
-        let buffer = state.read_next(std::mem::size_of::<Self>())?;
-        let value: Self =
-            Self::from_le_bytes(buffer.try_into().map_err(|_| DecodeError::BufferTooSmall)?);
-        Ok(value)
+    fn encode_dyn(&self, state: &mut State) -> EncodeResult {
+        self.encode(state)
     }
 }
 
-//
-// buffers, arrays
-//
-
-/// compact encoding for Option<&[u8]>
-impl Encode for Option<&[u8]> {
+/// compact encoding for trait objects
+/// the type id is encoded as usize, followed by the concrete value
+impl Encode for dyn EncodeDyn + '_ {
     /// allocate the required size in State for current type
     fn pre_encode(&self, state: &mut State) {
-        match self {
-            Some(buffer) => {
-                buffer.len().pre_encode(state);
-                state.end += buffer.len();
-            }
-            None => state.end += 1,
-        };
+        (self.dyn_type_id() as usize).pre_encode(state);
+        self.pre_encode_dyn(state);
     }
 
     /// encode n into state.buffer
     /// requires state.buffer to be allocated first
     fn encode(&self, state: &mut State) -> EncodeResult {
-        match self {
-            Some(buffer) => {
-                buffer.len().encode(state)?;
-                state.write(buffer)
-            }
-            None => state.write(&[0]),
-        }
+        (self.dyn_type_id() as usize).encode(state)?;
+        self.encode_dyn(state)
     }
 }
 
-/// compact decoding for Option<&[u8]>
-impl Decode for Option<Box<Vec<u8>>> {
-    fn decode(state: &mut State) -> DecodeResultT<Self> {
-        let buffer_size = usize::decode(state)?;
-        if buffer_size == 0 {
-            return Ok(None);
-        };
-        let buffer_ref = state.read_next(buffer_size)?;
+/// decoder for a concrete type, returning it as trait object `Box<T>`
+pub type DynDecoder<T> = fn(&mut State) -> DecodeResultT<Box<T>>;
 
-        if buffer_ref.len() == buffer_size {
-            Ok(Some(Box::new(Vec::from(buffer_ref))))
-        } else {
-            Err(DecodeError::TypeMismatch)
+/// registry of decoders for trait objects `Box<T>`, keyed by TypeTag::TYPE_ID
+///
+/// Every concrete type which is encoded as `dyn EncodeDyn` has to be registered before
+/// decoding, otherwise decode() returns TypeMismatch.
+pub struct DynRegistry<T: ?Sized> {
+    decoders: HashMap<u32, DynDecoder<T>>,
+}
+
+impl<T: ?Sized> Default for DynRegistry<T> {
+    fn default() -> Self {
+        Self {
+            decoders: HashMap::new(),
         }
     }
 }
 
-/// wrapper struct for encoding plain buffers without length information
-#[derive(Debug, PartialEq)]
-pub enum Raw<'a> {
-    /// enum variant which is returned by Decode
-    Vec(Vec<u8>), // TODO: Replace Vec with Box<Vec>?
-    VecRef(&'a Vec<u8>),
-    Slice(&'a [u8]),
+impl<T: ?Sized> DynRegistry<T> {
+    /// create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// register `decoder` for the concrete type C
+    /// a decoder registered before for the same TYPE_ID is replaced
+    pub fn register<C: TypeTag>(&mut self, decoder: DynDecoder<T>) {
+        self.decoders.insert(C::TYPE_ID, decoder);
+    }
+
+    /// decode the type id and the concrete value registered for it
+    /// Return TypeMismatch if no decoder is registered for the type id
+    pub fn decode(&self, state: &mut State) -> DecodeResultT<Box<T>> {
+        let type_id =
+            u32::try_from(usize::decode(state)?).map_err(|_| DecodeError::TypeMismatch)?;
+        let decoder = self
+            .decoders
+            .get(&type_id)
+            .ok_or(DecodeError::TypeMismatch)?;
+        decoder(state)
+    }
 }
 
-/// compact encoding for raw buffer
-impl<'a> Encode for Raw<'a> {
+//
+// numeric wrappers
+//
+
+/// compact encoding for Wrapping<T>, the inner value is encoded
+impl<T: Encode> Encode for std::num::Wrapping<T> {
     /// allocate the required size in State for current type
     fn pre_encode(&self, state: &mut State) {
-        match *self {
-            Raw::Vec(ref buffer) => state.end += buffer.len(),
-            Raw::VecRef(buffer) => state.end += buffer.len(),
-            Raw::Slice(slice) => state.end += slice.len(),
-        }
+        self.0.pre_encode(state);
     }
 
     /// encode n into state.buffer
     /// requires state.buffer to be allocated first
     fn encode(&self, state: &mut State) -> EncodeResult {
-        match *self {
-            Raw::Vec(ref buffer) => state.write(&buffer),
-            Raw::VecRef(buffer) => state.write(&buffer),
-            Raw::Slice(slice) => state.write(slice),
-        }
+        self.0.encode(state)
     }
 }
 
-/// compact decoding for Option<&[u8]>
-impl<'a> Decode for Raw<'a> {
+/// compact decoding for Wrapping<T>
+impl<T: Decode> Decode for std::num::Wrapping<T> {
     fn decode(state: &mut State) -> DecodeResultT<Self> {
-        let buffer_size = state.end - state.start;
-        if buffer_size == 0 {
-            Ok(Raw::Vec(vec![]))
-        } else {
-            let buffer_ref = state.read_next(buffer_size)?;
-
-            Ok(Raw::Vec(buffer_ref.into()))
-        }
+        T::decode(state).map(std::num::Wrapping)
     }
 }
 
-/// compact encoding for &str
-/// TODO: implement for Into<&str> instead?
-impl Encode for &str {
+/// compact encoding for Saturating<T>, the inner value is encoded
+impl<T: Encode> Encode for std::num::Saturating<T> {
     /// allocate the required size in State for current type
     fn pre_encode(&self, state: &mut State) {
-        // len always returns number of bytes
-        self.len().pre_encode(state);
-        state.end += self.len();
+        self.0.pre_encode(state);
     }
 
-    /// encode self into state.buffer
+    /// encode n into state.buffer
     /// requires state.buffer to be allocated first
     fn encode(&self, state: &mut State) -> EncodeResult {
-        self.len().encode(state)?;
-        state.write(self.as_bytes())
+        self.0.encode(state)
     }
 }
 
-/// compact decoding into String
-impl Decode for String {
+/// compact decoding for Saturating<T>
+impl<T: Decode> Decode for std::num::Saturating<T> {
     fn decode(state: &mut State) -> DecodeResultT<Self> {
-        let buffer_size = usize::decode(state)?;
-        if buffer_size == 0 {
-            return Ok("".into());
-        } else if (state.start + buffer_size) > state.end {
-            return Err(DecodeError::BufferTooSmall);
-        }
-        let buffer_ref = state.read_next(buffer_size)?;
-        /*
-            const s = b.toString(state.buffer, 'utf8', state.start, state.start += len)
-            if (b.byteLength(s) !== len || state.start > state.end) throw new Error('Out of bounds')
-        */
-        if buffer_ref.len() != buffer_size {
-            Err(DecodeError::BufferTooSmall)
-        } else {
-            Ok(String::from_utf8(buffer_ref.into()).map_err(|_| DecodeError::InvalidUtf8)?)
-        }
+        T::decode(state).map(std::num::Saturating)
     }
 }
 
-/// compact encoding for arrays [T; N]
-impl<T, const N: usize> Encode for [T; N]
-where
-    T: Encode,
-{
+/// compact encoding for AtomicU32
+///
+/// The value is loaded with Ordering::Relaxed, encoding is a point-in-time snapshot
+/// and not synchronized with concurrent updates.
+impl Encode for std::sync::atomic::AtomicU32 {
     /// allocate the required size in State for current type
     fn pre_encode(&self, state: &mut State) {
-        N.pre_encode(state);
-        // TODO check for MAX_ARRAY_DECODE_SIZE -> not implemented in JS
-        for element in self.iter() {
-            element.pre_encode(state);
-        }
+        self.load(std::sync::atomic::Ordering::Relaxed)
+            .pre_encode(state);
     }
 
-    /// encode self into state.buffer
+    /// encode n into state.buffer
     /// requires state.buffer to be allocated first
     fn encode(&self, state: &mut State) -> EncodeResult {
-        N.encode(state)?;
-        // TODO check for MAX_ARRAY_DECODE_SIZE -> not implemented in JS
-        for element in self.iter() {
-            element.encode(state)?;
-        }
-        Ok(())
+        self.load(std::sync::atomic::Ordering::Relaxed)
+            .encode(state)
     }
 }
 
-/// compact encoding for Vec<T>
-impl<T> Encode for Vec<T>
-where
-    T: Encode,
-{
+/// compact decoding for AtomicU32, decodes into a fresh atomic
+impl Decode for std::sync::atomic::AtomicU32 {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        u32::decode(state).map(std::sync::atomic::AtomicU32::new)
+    }
+}
+
+/// compact encoding for AtomicU64
+///
+/// The value is loaded with Ordering::Relaxed, encoding is a point-in-time snapshot
+/// and not synchronized with concurrent updates.
+impl Encode for std::sync::atomic::AtomicU64 {
     /// allocate the required size in State for current type
     fn pre_encode(&self, state: &mut State) {
-        self.len().pre_encode(state);
-        // TODO check for MAX_ARRAY_DECODE_SIZE
-        for element in self.iter() {
-            element.pre_encode(state);
-        }
+        self.load(std::sync::atomic::Ordering::Relaxed)
+            .pre_encode(state);
     }
 
-    /// encode self into state.buffer
+    /// encode n into state.buffer
     /// requires state.buffer to be allocated first
     fn encode(&self, state: &mut State) -> EncodeResult {
-        self.len().encode(state)?;
-        // TODO check for MAX_ARRAY_DECODE_SIZE
-        for element in self.iter() {
-            element.encode(state)?;
-        }
-        Ok(())
+        self.load(std::sync::atomic::Ordering::Relaxed)
+            .encode(state)
     }
 }
 
-/// compact decoding into Vec<T>
-impl<T> Decode for Vec<T>
-where
-    T: Decode,
-{
+/// compact decoding for AtomicU64, decodes into a fresh atomic
+impl Decode for std::sync::atomic::AtomicU64 {
     fn decode(state: &mut State) -> DecodeResultT<Self> {
-        let buffer_size = usize::decode(state)?;
-        if buffer_size == 0 {
-            return Ok(vec![]);
-        } else if (state.start + buffer_size) > state.end {
-            return Err(DecodeError::BufferTooSmall);
-        } else if buffer_size > MAX_ARRAY_DECODE_SIZE {
-            return Err(DecodeError::ArrayTooLarge);
-        }
-        let mut vec: Vec<T> = Vec::with_capacity(buffer_size);
-        for _ in 0..buffer_size {
-            vec.push(T::decode(state)?);
-        }
-        Ok(vec)
+        u64::decode(state).map(std::sync::atomic::AtomicU64::new)
     }
 }
 
-#[derive(Debug, PartialEq)]
-pub enum U32Array<'a> {
-    Vec(Vec<u32>),
-    VecRef(&'a Vec<u32>),
-    Slice(&'a [u32]),
-}
+//
+// uuid
+//
 
-/// compact encoding for U32Array
-/// MDN: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Uint32Array
-impl Encode for U32Array<'_> {
+/// compact encoding for Uuid, encoded as 16 raw bytes like Fixed<16>
+#[cfg(feature = "uuid")]
+impl Encode for uuid::Uuid {
     /// allocate the required size in State for current type
     fn pre_encode(&self, state: &mut State) {
-        let vec = match self {
-            U32Array::Vec(vec) => vec.as_slice(),
-            U32Array::VecRef(vec) => vec.as_slice(),
-            U32Array::Slice(slice) => slice,
-        };
-        vec.len().pre_encode(state);
-        // u32 has 4 bytes length
-        state.end += vec.len() * 4;
+        Fixed(*self.as_bytes()).pre_encode(state);
     }
 
     /// encode n into state.buffer
     /// requires state.buffer to be allocated first
     fn encode(&self, state: &mut State) -> EncodeResult {
-        let vec = match self {
-            U32Array::Vec(vec) => vec.as_slice(),
-            U32Array::VecRef(vec) => vec.as_slice(),
-            U32Array::Slice(slice) => slice,
-        };
-        vec.len().encode(state)?;
-        for num in vec {
-            state.write(&num.to_le_bytes())?;
-        }
-        Ok(())
+        Fixed(*self.as_bytes()).encode(state)
     }
 }
 
-/// compact decoding for U32Array
-/// returns U32Array::Vec(_)
-impl Decode for U32Array<'_> {
+/// compact decoding for Uuid
+#[cfg(feature = "uuid")]
+impl Decode for uuid::Uuid {
     fn decode(state: &mut State) -> DecodeResultT<Self> {
-        let buffer_size = usize::decode(state)?;
-        if buffer_size == 0 {
-            return Ok(U32Array::Vec(vec![]));
-        };
-        /* JS Implementation contains this part as well
-         * TODO: clarify functionality with original author
-            // const byteOffset = state.buffer.byteOffset + state.start
-            // const s = state.start
-
-            // state.start += len * 4
-
-            // if ((byteOffset & 3) === 0) {
-            //   const arr = new Uint32Array(state.buffer.buffer, byteOffset, len)
-            //   if (BE) LEToHost32(arr, len)
-            //   return arr
-            // }
-        */
-        // align mismatch
-        let mut vec: Vec<u32> = Vec::with_capacity(buffer_size);
-        // read all u32 values and decode them from little endian
-        // difference to JS implementation: decode each value instead of reading buffer and then decoding buffer
-        for _ in 1..(buffer_size + 1) {
-            let buffer_ref = state.read_next(4)?;
-            vec.push(u32::from_le_bytes(
-                buffer_ref
-                    .try_into()
-                    .map_err(|_| DecodeError::TypeMismatch)?,
-            ));
-        }
-        Ok(U32Array::Vec(vec))
+        Fixed::<16>::decode(state).map(|fixed| uuid::Uuid::from_bytes(fixed.0))
     }
 }
 
-/// compact encoding for fixed size buffers
-#[derive(Debug, PartialEq, PartialOrd)]
-pub struct Fixed<const N: usize>([u8; N]);
-
-pub type Fixed32 = Fixed<32>;
-pub type Fixed64 = Fixed<64>;
+//
+// smallvec
+//
 
-/// compact encoding for Fixed<N>
-impl<const N: usize> Encode for Fixed<N> {
+/// compact encoding for SmallVec<[T; N]>, same layout as Vec<T>
+#[cfg(feature = "smallvec")]
+impl<A> Encode for smallvec::SmallVec<A>
+where
+    A: smallvec::Array,
+    A::Item: Encode,
+{
     /// allocate the required size in State for current type
     fn pre_encode(&self, state: &mut State) {
-        state.end += N;
+        self.as_slice().pre_encode(state);
     }
 
     /// encode n into state.buffer
     /// requires state.buffer to be allocated first
     fn encode(&self, state: &mut State) -> EncodeResult {
-        state.write(&self.0[..])
+        self.as_slice().encode(state)
     }
 }
 
-/// compact decoding for Fixed<N>
-impl<const N: usize> Decode for Fixed<N> {
+/// compact decoding for SmallVec<[T; N]>
+/// the elements are stored inline if there are at most N of them
+/// return an error if the encoded length exceeds MAX_ARRAY_DECODE_SIZE
+#[cfg(feature = "smallvec")]
+impl<A> Decode for smallvec::SmallVec<A>
+where
+    A: smallvec::Array,
+    A::Item: Decode,
+{
     fn decode(state: &mut State) -> DecodeResultT<Self> {
-        let buffer_ref = state.read_next(N)?;
-        let mut fixed = Self([0; N]);
-        fixed.0.copy_from_slice(buffer_ref);
-        Ok(fixed)
+        let length = state.read_count(MAX_ARRAY_DECODE_SIZE)?;
+        let mut vec = Self::with_capacity(length);
+        if length > 0 {
+            A::Item::decode_extend(state, length, &mut vec)?;
+        }
+        Ok(vec)
     }
 }
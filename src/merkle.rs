@@ -0,0 +1,70 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+//! compact encoding for merkle proofs
+//!
+//! The layout is:
+//!
+//! * leaf index as compact uint with the smallest possible prefix
+//! * leaf hash as 32 raw bytes
+//! * sibling hashes from the leaf level up to the root like Vec<Fixed32>,
+//!   the number of siblings followed by 32 raw bytes each
+
+use crate::error::*;
+use crate::{CompactU64, Decode, Encode, Fixed32, State};
+
+/// proof that a leaf is part of a merkle tree with 32 byte hashes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleProof {
+    /// index of the leaf in the tree, the bits select the side of each sibling
+    pub leaf_index: u64,
+    pub leaf: Fixed32,
+    /// sibling hashes ordered from the leaf level up to the root
+    pub siblings: Vec<Fixed32>,
+}
+
+impl MerkleProof {
+    /// compute the root hash using `hash` to combine the left and right child
+    pub fn root<F>(&self, hash: F) -> Fixed32
+    where
+        F: Fn(&Fixed32, &Fixed32) -> Fixed32,
+    {
+        self.siblings
+            .iter()
+            .enumerate()
+            .fold(self.leaf.clone(), |node, (level, sibling)| {
+                match (self.leaf_index >> level.min(63)) & 1 {
+                    0 => hash(&node, sibling),
+                    _ => hash(sibling, &node),
+                }
+            })
+    }
+}
+
+/// compact encoding for MerkleProof
+impl Encode for MerkleProof {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        CompactU64(self.leaf_index).pre_encode(state);
+        self.leaf.pre_encode(state);
+        self.siblings.pre_encode(state);
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        CompactU64(self.leaf_index).encode(state)?;
+        self.leaf.encode(state)?;
+        self.siblings.encode(state)
+    }
+}
+
+/// compact decoding for MerkleProof
+impl Decode for MerkleProof {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        Ok(Self {
+            leaf_index: CompactU64::decode(state)?.0,
+            leaf: Fixed32::decode(state)?,
+            siblings: Vec::decode(state)?,
+        })
+    }
+}
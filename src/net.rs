@@ -0,0 +1,322 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+//! compact encoding for IP addresses and socket addresses
+//!
+//! The layout follows `<https://github.com/compact-encoding/compact-encoding-net>`:
+//!
+//! * `Ipv4Addr`: 4 bytes
+//! * `Ipv6Addr`: 16 bytes
+//! * `IpAddr`: family byte (4 or 6) followed by the address
+//! * `SocketAddrV4`: address followed by the port as 2 byte little-endian integer
+//! * `SocketAddrV6`: address, port, and additionally flowinfo and scope id
+//!   as 4 byte little-endian integers each
+//! * `SocketAddr`: family byte (4 or 6) followed by the socket address
+//! * `DomainName`: length-prefixed labels terminated by a zero byte, as in DNS, with compression pointers when decoding
+
+use crate::error::*;
+use crate::{Decode, Encode, Reader, State, Writer};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+const IPV4_FAMILY: u8 = 4;
+const IPV6_FAMILY: u8 = 6;
+
+/// maximum length of a single label of a domain name
+pub const MAX_LABEL_LENGTH: usize = 63;
+/// maximum length of an encoded domain name, including length bytes and terminator
+pub const MAX_DOMAIN_NAME_LENGTH: usize = 255;
+
+/// read a little-endian u16 without prefix
+fn decode_u16_le(state: &mut State) -> DecodeResultT<u16> {
+    let buffer = state.read_next(2)?;
+    Ok(u16::from_le_bytes([buffer[0], buffer[1]]))
+}
+
+/// read a little-endian u32 without prefix
+fn decode_u32_le(state: &mut State) -> DecodeResultT<u32> {
+    let buffer = state.read_next(4)?;
+    Ok(u32::from_le_bytes([
+        buffer[0], buffer[1], buffer[2], buffer[3],
+    ]))
+}
+
+/// compact encoding for Ipv4Addr
+impl Encode for Ipv4Addr {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        state.end += 4;
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        state.write(&self.octets())
+    }
+}
+
+/// compact decoding for Ipv4Addr
+impl Decode for Ipv4Addr {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        let buffer = state.read_next(4)?;
+        Ok(Ipv4Addr::new(buffer[0], buffer[1], buffer[2], buffer[3]))
+    }
+}
+
+/// compact encoding for Ipv6Addr
+impl Encode for Ipv6Addr {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        state.end += 16;
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        state.write(&self.octets())
+    }
+}
+
+/// compact decoding for Ipv6Addr
+impl Decode for Ipv6Addr {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        let octets: [u8; 16] = *state.decode_fixed_ref()?;
+        Ok(Ipv6Addr::from(octets))
+    }
+}
+
+/// compact encoding for IpAddr
+impl Encode for IpAddr {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        state.end += 1;
+        match self {
+            IpAddr::V4(address) => address.pre_encode(state),
+            IpAddr::V6(address) => address.pre_encode(state),
+        }
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        match self {
+            IpAddr::V4(address) => {
+                state.write(&[IPV4_FAMILY])?;
+                address.encode(state)
+            }
+            IpAddr::V6(address) => {
+                state.write(&[IPV6_FAMILY])?;
+                address.encode(state)
+            }
+        }
+    }
+}
+
+/// compact decoding for IpAddr
+/// return an error if the family byte is unknown
+impl Decode for IpAddr {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        match state.read_next(1)?[0] {
+            IPV4_FAMILY => Ipv4Addr::decode(state).map(IpAddr::V4),
+            IPV6_FAMILY => Ipv6Addr::decode(state).map(IpAddr::V6),
+            _ => Err(DecodeError::TypeMismatch),
+        }
+    }
+}
+
+/// compact encoding for SocketAddrV4
+impl Encode for SocketAddrV4 {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        self.ip().pre_encode(state);
+        state.end += 2;
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        self.ip().encode(state)?;
+        state.write(&self.port().to_le_bytes())
+    }
+}
+
+/// compact decoding for SocketAddrV4
+impl Decode for SocketAddrV4 {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        let ip = Ipv4Addr::decode(state)?;
+        let port = decode_u16_le(state)?;
+        Ok(SocketAddrV4::new(ip, port))
+    }
+}
+
+/// compact encoding for SocketAddrV6
+impl Encode for SocketAddrV6 {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        self.ip().pre_encode(state);
+        state.end += 2 + 4 + 4;
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        self.ip().encode(state)?;
+        state.write(&self.port().to_le_bytes())?;
+        state.write(&self.flowinfo().to_le_bytes())?;
+        state.write(&self.scope_id().to_le_bytes())
+    }
+}
+
+/// compact decoding for SocketAddrV6
+impl Decode for SocketAddrV6 {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        let ip = Ipv6Addr::decode(state)?;
+        let port = decode_u16_le(state)?;
+        let flowinfo = decode_u32_le(state)?;
+        let scope_id = decode_u32_le(state)?;
+        Ok(SocketAddrV6::new(ip, port, flowinfo, scope_id))
+    }
+}
+
+/// compact encoding for SocketAddr
+impl Encode for SocketAddr {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        state.end += 1;
+        match self {
+            SocketAddr::V4(address) => address.pre_encode(state),
+            SocketAddr::V6(address) => address.pre_encode(state),
+        }
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        match self {
+            SocketAddr::V4(address) => {
+                state.write(&[IPV4_FAMILY])?;
+                address.encode(state)
+            }
+            SocketAddr::V6(address) => {
+                state.write(&[IPV6_FAMILY])?;
+                address.encode(state)
+            }
+        }
+    }
+}
+
+/// compact decoding for SocketAddr
+/// return an error if the family byte is unknown
+impl Decode for SocketAddr {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        match state.read_next(1)?[0] {
+            IPV4_FAMILY => SocketAddrV4::decode(state).map(SocketAddr::V4),
+            IPV6_FAMILY => SocketAddrV6::decode(state).map(SocketAddr::V6),
+            _ => Err(DecodeError::TypeMismatch),
+        }
+    }
+}
+
+/// domain name encoded as a sequence of labels, like in DNS messages
+///
+/// Each label is encoded as a length byte followed by its bytes, the name is terminated by a zero byte.
+/// Names are always encoded uncompressed. When decoding, compression pointers are followed:
+/// two bytes starting with the bits 0b11 replace the remaining labels by the labels at the 14 bit offset,
+/// which is the absolute position in the decoded buffer and must be located before the name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct DomainName(String);
+
+impl DomainName {
+    /// create a domain name from its dotted representation, a single trailing dot is ignored
+    /// return None if a label is empty or longer than MAX_LABEL_LENGTH,
+    /// or if the encoded name is longer than MAX_DOMAIN_NAME_LENGTH
+    pub fn new(name: &str) -> Option<Self> {
+        let name = name.strip_suffix('.').unwrap_or(name);
+        let domain_name = Self(name.to_string());
+        if domain_name
+            .labels()
+            .any(|label| label.is_empty() || label.len() > MAX_LABEL_LENGTH)
+            || domain_name.encoded_len() > MAX_DOMAIN_NAME_LENGTH
+        {
+            return None;
+        }
+        Some(domain_name)
+    }
+
+    /// return the dotted representation without trailing dot
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// return an iterator over the labels, which is empty for the root domain
+    pub fn labels(&self) -> impl Iterator<Item = &str> {
+        self.0.split('.').filter(|_| !self.0.is_empty())
+    }
+
+    fn encoded_len(&self) -> usize {
+        self.labels().map(|label| 1 + label.len()).sum::<usize>() + 1
+    }
+}
+
+/// compact encoding for DomainName
+impl Encode for DomainName {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        state.end += self.encoded_len();
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        for label in self.labels() {
+            state.write(&[label.len() as u8])?;
+            state.write(label.as_bytes())?;
+        }
+        state.write(&[0])
+    }
+}
+
+/// compact decoding for DomainName
+/// return an error if a label is too long, a compression pointer does not point backwards,
+/// or the name exceeds MAX_DOMAIN_NAME_LENGTH
+impl Decode for DomainName {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        let mut labels = Vec::new();
+        let mut length = 1;
+        // every pointer must point before the previous one, so pointers can not loop
+        let mut limit = state.position();
+        // position after the first pointer, where decoding continues
+        let mut end = None;
+        loop {
+            let label_length = state.read_next(1)?[0] as usize;
+            if label_length == 0 {
+                break;
+            }
+            if label_length & 0xC0 == 0xC0 {
+                let offset = (label_length & 0x3F) << 8 | state.read_next(1)?[0] as usize;
+                if offset >= limit {
+                    return Err(DecodeError::TypeMismatch);
+                }
+                end.get_or_insert(state.position());
+                limit = offset;
+                state.seek(offset)?;
+                continue;
+            }
+            if label_length > MAX_LABEL_LENGTH {
+                return Err(DecodeError::TypeMismatch);
+            }
+            length += 1 + label_length;
+            if length > MAX_DOMAIN_NAME_LENGTH {
+                return Err(DecodeError::StringTooLarge);
+            }
+            let label = std::str::from_utf8(state.read_next(label_length)?)
+                .map_err(|_| DecodeError::InvalidUtf8)?;
+            if label.contains('.') {
+                return Err(DecodeError::TypeMismatch);
+            }
+            labels.push(label.to_string());
+        }
+        if let Some(end) = end {
+            state.seek(end)?;
+        }
+        Ok(Self(labels.join(".")))
+    }
+}
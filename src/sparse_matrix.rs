@@ -0,0 +1,161 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+//! compact encoding for sparse matrices in compressed sparse row (CSR) format
+//!
+//! The layout is:
+//!
+//! * number of rows and columns as usize
+//! * number of entries of each row as usize, the row pointers are their running sum
+//! * column index of each entry as usize, delta encoded within each row
+//!   (the first entry of a row is absolute)
+//! * values of all entries like Vec<T>
+
+use crate::error::*;
+use crate::{Decode, Encode, State, MAX_ARRAY_DECODE_SIZE};
+
+/// sparse matrix in compressed sparse row (CSR) format
+///
+/// The entries of row `r` are `values[row_ptr[r]..row_ptr[r + 1]]`
+/// in the columns `col_indices[row_ptr[r]..row_ptr[r + 1]]`, which are strictly increasing.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SparseMatrix<T> {
+    rows: usize,
+    cols: usize,
+    row_ptr: Vec<usize>,
+    col_indices: Vec<usize>,
+    values: Vec<T>,
+}
+
+impl<T> SparseMatrix<T> {
+    /// create a matrix from its CSR components
+    /// return None if the components are inconsistent
+    pub fn new(
+        rows: usize,
+        cols: usize,
+        row_ptr: Vec<usize>,
+        col_indices: Vec<usize>,
+        values: Vec<T>,
+    ) -> Option<Self> {
+        let valid = row_ptr.len().checked_sub(1) == Some(rows)
+            && row_ptr[0] == 0
+            && row_ptr[rows] == values.len()
+            && col_indices.len() == values.len()
+            && row_ptr.windows(2).all(|range| {
+                range[0] <= range[1]
+                    && range[1] <= col_indices.len()
+                    && col_indices[range[0]..range[1]]
+                        .windows(2)
+                        .all(|cols| cols[0] < cols[1])
+                    && col_indices[range[0]..range[1]]
+                        .last()
+                        .is_none_or(|col| *col < cols)
+            });
+        valid.then_some(Self {
+            rows,
+            cols,
+            row_ptr,
+            col_indices,
+            values,
+        })
+    }
+
+    /// return the number of rows and columns
+    pub fn dims(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    /// return the row pointers, with rows + 1 elements
+    pub fn row_ptr(&self) -> &[usize] {
+        &self.row_ptr
+    }
+
+    /// return the column index of each entry
+    pub fn col_indices(&self) -> &[usize] {
+        &self.col_indices
+    }
+
+    /// return the value of each entry
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// return the entry at `row` and `col`, or None if it is not stored
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        let range = *self.row_ptr.get(row)?..*self.row_ptr.get(row.checked_add(1)?)?;
+        let index = self.col_indices[range.clone()].binary_search(&col).ok()?;
+        self.values.get(range.start + index)
+    }
+
+    /// iterate over the column deltas of all entries, restarting at each row
+    fn col_deltas(&self) -> impl Iterator<Item = usize> + '_ {
+        self.row_ptr.windows(2).flat_map(|range| {
+            let cols = &self.col_indices[range[0]..range[1]];
+            cols.iter().scan(0, |previous, col| {
+                Some(col - std::mem::replace(previous, *col))
+            })
+        })
+    }
+}
+
+/// compact encoding for SparseMatrix<T>
+impl<T: Encode> Encode for SparseMatrix<T> {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        self.rows.pre_encode(state);
+        self.cols.pre_encode(state);
+        for range in self.row_ptr.windows(2) {
+            (range[1] - range[0]).pre_encode(state);
+        }
+        for delta in self.col_deltas() {
+            delta.pre_encode(state);
+        }
+        self.values.pre_encode(state);
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        self.rows.encode(state)?;
+        self.cols.encode(state)?;
+        for range in self.row_ptr.windows(2) {
+            (range[1] - range[0]).encode(state)?;
+        }
+        for delta in self.col_deltas() {
+            delta.encode(state)?;
+        }
+        self.values.encode(state)
+    }
+}
+
+/// compact decoding for SparseMatrix<T>
+/// return TypeMismatch if the decoded components are inconsistent
+impl<T: Decode> Decode for SparseMatrix<T> {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        let rows = state.read_count(MAX_ARRAY_DECODE_SIZE)?;
+        let cols = usize::decode(state)?;
+        let mut row_ptr = Vec::with_capacity(rows + 1);
+        row_ptr.push(0);
+        for _ in 0..rows {
+            let entries = state.read_count(MAX_ARRAY_DECODE_SIZE)?;
+            let end = row_ptr[row_ptr.len() - 1] + entries;
+            if end > MAX_ARRAY_DECODE_SIZE {
+                return Err(DecodeError::ArrayTooLarge);
+            }
+            row_ptr.push(end);
+        }
+        let mut col_indices = Vec::with_capacity(row_ptr[rows].min(state.remaining()));
+        for range in row_ptr.windows(2) {
+            let mut col = 0usize;
+            for index in range[0]..range[1] {
+                let delta = usize::decode(state)?;
+                if index > range[0] && delta == 0 {
+                    return Err(DecodeError::TypeMismatch);
+                }
+                col = col.checked_add(delta).ok_or(DecodeError::TypeMismatch)?;
+                col_indices.push(col);
+            }
+        }
+        let values = Vec::<T>::decode(state)?;
+        Self::new(rows, cols, row_ptr, col_indices, values).ok_or(DecodeError::TypeMismatch)
+    }
+}
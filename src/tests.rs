@@ -1,17 +1,94 @@
 // SPDX-License-Identifier: MIT
 // compact-encoding-rs Authors: see AUTHORS.txt
 
+// the tests adapted from the JS library are kept close to their original form
 #[cfg(test)]
+#[allow(
+    clippy::char_lit_as_u8,
+    clippy::clone_on_copy,
+    clippy::unnecessary_cast
+)]
 mod npm_adapted;
 
 #[cfg(test)]
 mod basic;
 
 #[cfg(test)]
+#[allow(clippy::useless_conversion)]
 mod buffer;
 
 #[cfg(test)]
+#[allow(clippy::excessive_precision)]
 mod float;
 
 #[cfg(test)]
 mod unsigned;
+
+#[cfg(test)]
+mod signed;
+
+#[cfg(test)]
+mod state;
+
+#[cfg(test)]
+mod array;
+
+#[cfg(test)]
+mod helpers;
+
+#[cfg(test)]
+mod wrapper;
+
+#[cfg(test)]
+mod nested;
+
+#[cfg(test)]
+mod leb128;
+
+#[cfg(test)]
+mod property;
+
+#[cfg(test)]
+mod net;
+
+#[cfg(all(test, feature = "uuid"))]
+mod uuid;
+
+#[cfg(test)]
+mod tuple;
+
+#[cfg(test)]
+mod map;
+
+#[cfg(test)]
+mod dynamic;
+
+#[cfg(test)]
+mod trie;
+
+#[cfg(test)]
+mod reader;
+
+#[cfg(test)]
+mod event_log;
+
+#[cfg(test)]
+mod interval_tree;
+
+#[cfg(test)]
+mod sparse_matrix;
+
+#[cfg(test)]
+mod merkle;
+
+#[cfg(all(test, feature = "smallvec"))]
+mod smallvec;
+
+#[cfg(all(test, feature = "compression"))]
+mod compression;
+
+#[cfg(all(test, feature = "bumpalo"))]
+mod arena;
+
+#[cfg(test)]
+mod util;
@@ -0,0 +1,49 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+
+use crate::arena::*;
+use crate::*;
+use bumpalo::Bump;
+
+#[test]
+fn test_arena_strings() {
+    let names = ["alice", "", "bob", "ünïcödé"];
+    let mut state = State::new();
+    for name in names {
+        state.encode_append(name).unwrap();
+    }
+    state.encode_append(&vec![1u8, 2, 3]).unwrap();
+    state.encode_append(&vec![300u32, 70_000]).unwrap();
+
+    let arena = Bump::new();
+    state.start = 0;
+    let decoded: Vec<&str> = names
+        .iter()
+        .map(|_| decode_str_in(&mut state, &arena).unwrap())
+        .collect();
+    assert_eq!(decoded, names);
+    assert_eq!(decode_buffer_in(&mut state, &arena), Ok(&[1u8, 2, 3][..]));
+    let numbers = decode_vec_in::<u32>(&mut state, &arena).unwrap();
+    assert_eq!(numbers.as_slice(), [300, 70_000]);
+    assert_eq!(state.start, state.end);
+    assert!(arena.allocated_bytes() > 0);
+}
+
+#[test]
+fn test_arena_invalid() {
+    let arena = Bump::new();
+    let mut state = State::from_buffer(vec![2, 0xC3, 0x28]);
+    assert_eq!(
+        decode_str_in(&mut state, &arena),
+        Err(DecodeError::InvalidUtf8)
+    );
+
+    let mut state = State::from_buffer(vec![3, b'a']);
+    assert_eq!(
+        decode_str_in(&mut state, &arena),
+        Err(DecodeError::InvalidLength {
+            declared: 3,
+            available: 1
+        })
+    );
+}
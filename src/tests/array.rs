@@ -0,0 +1,394 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+
+use crate::*;
+
+//
+// arrays [T; N]
+//
+
+#[test]
+fn test_array_decode() {
+    let mut state = State::new();
+    let array = [1u32, 2, 3];
+    array.pre_encode(&mut state);
+    state.alloc();
+    assert_eq!(array.encode(&mut state), Ok(()));
+
+    state.start = 0;
+    assert_eq!(<[u32; 3]>::decode(&mut state), Ok(array));
+    assert_eq!(state.start, state.end);
+}
+
+#[test]
+fn test_array_decode_length_mismatch() {
+    let mut state = State::new();
+    let array = [true, false, true];
+    array.pre_encode(&mut state);
+    state.alloc();
+    assert_eq!(array.encode(&mut state), Ok(()));
+
+    state.start = 0;
+    assert_eq!(
+        <[bool; 2]>::decode(&mut state),
+        Err(DecodeError::TypeMismatch)
+    );
+}
+
+#[test]
+fn test_char_array() {
+    let mut state = State::new();
+    let array = ['a', '🌾', 'z'];
+    array.pre_encode(&mut state);
+    assert_eq!(
+        state,
+        State {
+            start: 0,
+            end: 8, // length + 'a' + '🌾' (u32) + 'z'
+            buffer: None,
+            ..Default::default()
+        }
+    );
+    state.alloc();
+    assert_eq!(array.encode(&mut state), Ok(()));
+    assert_eq!(
+        state,
+        State {
+            start: 8,
+            end: 8,
+            buffer: Some(vec![3, b'a', 0xFE, 0x3E, 0xF3, 0x01, 0x00, b'z']),
+            ..Default::default()
+        }
+    );
+
+    state.start = 0;
+    assert_eq!(<[char; 3]>::decode(&mut state), Ok(array));
+    assert_eq!(state.start, state.end);
+}
+
+#[test]
+fn test_byte_array() {
+    let mut state = State::new();
+    let array = [0xFFu8, 0x00, 0xFD, 0x7F];
+    array.pre_encode(&mut state);
+    assert_eq!(state.end, 4); // raw bytes without length
+    state.alloc();
+    assert_eq!(array.encode(&mut state), Ok(()));
+    assert_eq!(state.buffer, Some(vec![0xFF, 0x00, 0xFD, 0x7F]));
+
+    state.start = 0;
+    assert_eq!(<[u8; 4]>::decode(&mut state), Ok(array));
+    assert_eq!(state.start, state.end);
+
+    state.start = 1;
+    assert_eq!(
+        <[u8; 4]>::decode(&mut state),
+        Err(DecodeError::BufferTooSmall)
+    );
+}
+
+#[test]
+fn test_empty_array() {
+    // byte arrays are raw bytes, so [u8; 0] occupies no bytes at all
+    let bytes: [u8; 0] = [];
+    assert_eq!(encode_to_vec(&bytes), Ok(vec![]));
+    let mut state = State::from_buffer(vec![]);
+    assert_eq!(<[u8; 0]>::decode(&mut state), Ok(bytes));
+    assert_eq!(state.start, 0);
+
+    // other arrays encode their length 0
+    let strings: [String; 0] = [];
+    assert_eq!(encode_to_vec(&strings), Ok(vec![0]));
+    let mut state = State::from_buffer(vec![0]);
+    assert_eq!(<[String; 0]>::decode(&mut state), Ok(strings));
+    assert_eq!(state.start, state.end);
+
+    let mut state = State::from_buffer(vec![1, 0]);
+    assert_eq!(
+        <[String; 0]>::decode(&mut state),
+        Err(DecodeError::TypeMismatch)
+    );
+}
+
+#[test]
+fn test_nested_byte_array() {
+    let mut state = State::new();
+    let array = [[1u8, 2], [3, 4]];
+    array.pre_encode(&mut state);
+    state.alloc();
+    assert_eq!(array.encode(&mut state), Ok(()));
+    assert_eq!(state.buffer, Some(vec![2, 1, 2, 3, 4]));
+
+    state.start = 0;
+    assert_eq!(<[[u8; 2]; 2]>::decode(&mut state), Ok(array));
+}
+
+//
+// slices
+//
+
+#[test]
+fn test_slice_of_strings() {
+    let strings = vec![String::from("a"), String::from("bc")];
+    let slice: &[String] = &strings;
+
+    let mut state = State::new();
+    slice.pre_encode(&mut state);
+    state.alloc();
+    assert_eq!(slice.encode(&mut state), Ok(()));
+    assert!(state.eq_bytes(&[2, 1, b'a', 2, b'b', b'c']));
+    assert_eq!(encode_to_vec(&slice), encode_to_vec(&strings));
+
+    state.start = 0;
+    assert_eq!(Vec::<String>::decode(&mut state), Ok(strings));
+}
+
+#[test]
+fn test_slice_of_pairs() {
+    let pairs = [(1u8, 2u8), (0xFF, 0)];
+    let slice: &[(u8, u8)] = &pairs[..];
+    let buffer = encode_to_vec(&slice).unwrap();
+    assert_eq!(buffer, vec![2, 1, 2, 0xFD, 0xFF, 0x00, 0]);
+    assert_eq!(
+        Vec::<(u8, u8)>::decode(&mut State::from_buffer(buffer)),
+        Ok(pairs.to_vec())
+    );
+
+    // slices of bytes are encoded as buffer
+    let bytes: &[u8] = &[1, 2, 3];
+    assert_eq!(encode_to_vec(bytes), Ok(vec![3, 1, 2, 3]));
+    assert_eq!(encode_to_vec(&&&bytes), Ok(vec![3, 1, 2, 3]));
+}
+
+//
+// boxed slices
+//
+
+#[test]
+fn test_boxed_slice() {
+    let boxed: Box<[u32]> = vec![1, 300, u32::MAX].into_boxed_slice();
+    let buffer = encode_to_vec(&boxed).unwrap();
+    // same layout as Vec<T>
+    assert_eq!(Ok(&buffer), encode_to_vec(&boxed.to_vec()).as_ref());
+
+    let mut state = State::from_buffer(buffer);
+    assert_eq!(<Box<[u32]>>::decode(&mut state), Ok(boxed));
+    assert_eq!(state.start, state.end);
+
+    let bytes: Box<[u8]> = Box::new([1, 2, 3]);
+    assert_eq!(encode_to_vec(&bytes), Ok(vec![3, 1, 2, 3]));
+}
+
+#[test]
+fn test_vec_of_zero_sized() {
+    // only the count is encoded, it may exceed the remaining bytes
+    let units = vec![(), (), ()];
+    let buffer = encode_to_vec(&units).unwrap();
+    assert_eq!(buffer, vec![3]);
+    let mut state = State::from_buffer(buffer);
+    assert_eq!(Vec::<()>::decode(&mut state), Ok(units));
+    assert_eq!(state.start, state.end);
+
+    // the element limit still applies
+    let mut state = State::from_buffer(encode_to_vec(&(MAX_ARRAY_DECODE_SIZE + 1)).unwrap());
+    assert_eq!(
+        Vec::<()>::decode(&mut state),
+        Err(DecodeError::ArrayTooLarge)
+    );
+}
+
+//
+// packed booleans
+//
+
+fn packed_bools_round_trip(count: usize) {
+    let values: Vec<bool> = (0..count).map(|i| i % 3 == 0).collect();
+    let packed = PackedBools(values.clone());
+
+    let mut state = State::new();
+    packed.pre_encode(&mut state);
+    assert_eq!(state.end, 1 + count.div_ceil(8));
+    state.alloc();
+    assert_eq!(packed.encode(&mut state), Ok(()));
+
+    state.start = 0;
+    assert_eq!(PackedBools::decode(&mut state), Ok(PackedBools(values)));
+    assert_eq!(state.start, state.end);
+}
+
+#[test]
+fn test_packed_bools() {
+    for count in [0, 7, 8, 13] {
+        packed_bools_round_trip(count);
+    }
+
+    assert_eq!(
+        encode_to_vec(&PackedBools(vec![
+            true, false, true, true, false, false, false, false, true
+        ])),
+        Ok(vec![9, 0b0000_1101, 0b0000_0001])
+    );
+}
+
+#[test]
+fn test_packed_bools_invalid() {
+    // bit for a 4th element is set
+    let mut state = State::from_buffer(vec![3, 0b0000_1001]);
+    assert_eq!(
+        PackedBools::decode(&mut state),
+        Err(DecodeError::TypeMismatch)
+    );
+
+    let mut state = State::from_buffer(vec![13, 0xFF]);
+    assert_eq!(
+        PackedBools::decode(&mut state),
+        Err(DecodeError::InvalidLength {
+            declared: 2,
+            available: 1
+        })
+    );
+}
+
+//
+// sparse sequences
+//
+
+#[test]
+fn test_sparse() {
+    let mut values: Vec<Option<u32>> = vec![None; 1000];
+    values[3] = Some(7);
+    values[500] = Some(70_000);
+    values[999] = Some(0);
+    let sparse = Sparse(values);
+
+    let buffer = encode_to_vec(&sparse).unwrap();
+    assert_eq!(buffer[..6], [0xFD, 0xE8, 0x03, 3, 3, 0xFE]);
+    // naive encoding: length + discriminant for each element + values
+    let naive_size = encoded_size(&1000usize)
+        + sparse
+            .0
+            .iter()
+            .map(|value| 1 + value.map_or(0, |value| encoded_size(&value)))
+            .sum::<usize>();
+    assert!(buffer.len() < naive_size / 10);
+
+    let mut state = State::from_buffer(buffer);
+    assert_eq!(Sparse::<u32>::decode(&mut state), Ok(sparse));
+    assert_eq!(state.start, state.end);
+
+    let empty: Sparse<String> = Sparse(vec![None; 3]);
+    assert_eq!(encode_to_vec(&empty), Ok(vec![3, 0]));
+    assert_eq!(
+        Sparse::decode(&mut State::from_buffer(vec![3, 0])),
+        Ok(empty)
+    );
+}
+
+#[test]
+fn test_sparse_invalid() {
+    // index out of range
+    let mut state = State::from_buffer(vec![3, 1, 3, 1]);
+    assert_eq!(
+        Sparse::<u8>::decode(&mut state),
+        Err(DecodeError::TypeMismatch)
+    );
+
+    // indices not increasing
+    let mut state = State::from_buffer(vec![3, 2, 1, 1, 1, 1]);
+    assert_eq!(
+        Sparse::<u8>::decode(&mut state),
+        Err(DecodeError::TypeMismatch)
+    );
+
+    // more entries than elements
+    let mut state = State::from_buffer(vec![1, 2, 0, 1, 1, 1]);
+    assert_eq!(
+        Sparse::<u8>::decode(&mut state),
+        Err(DecodeError::ArrayTooLarge)
+    );
+}
+
+#[test]
+fn test_column() {
+    let column = Column(vec![
+        Some(1u32),
+        None,
+        Some(70_000),
+        None,
+        None,
+        Some(3),
+        None,
+        None,
+        Some(0),
+    ]);
+
+    let buffer = encode_to_vec(&column).unwrap();
+    // 9 elements, presence bitmap in 2 bytes, followed by the 4 values
+    assert_eq!(buffer[..3], [9, 0b0010_0101, 0b0000_0001]);
+    assert_eq!(buffer.len(), 3 + 4 * encoded_size(&0u32));
+    // naive encoding: length + discriminant for each element + values
+    let naive_size = encoded_size(&9usize)
+        + column
+            .0
+            .iter()
+            .map(|value| 1 + value.map_or(0, |value| encoded_size(&value)))
+            .sum::<usize>();
+    assert!(buffer.len() < naive_size);
+    assert!(buffer.len() < encoded_size(&Sparse(column.0.clone())));
+
+    let mut state = State::from_buffer(buffer);
+    assert_eq!(Column::<u32>::decode(&mut state), Ok(column));
+    assert_eq!(state.start, state.end);
+
+    let empty: Column<String> = Column(vec![None; 3]);
+    assert_eq!(encode_to_vec(&empty), Ok(vec![3, 0]));
+    assert_eq!(
+        Column::decode(&mut State::from_buffer(vec![3, 0])),
+        Ok(empty)
+    );
+
+    // value of present element is missing
+    assert_eq!(
+        Column::<u8>::decode(&mut State::from_buffer(vec![2, 0b11, 5])),
+        Err(DecodeError::BufferTooSmall)
+    );
+}
+
+//
+// streaming decode
+//
+
+#[test]
+fn test_decode_iter() {
+    const COUNT: u32 = 1_000_000;
+    let mut state = State::new();
+    COUNT.pre_encode(&mut state);
+    for i in 0..COUNT {
+        i.pre_encode(&mut state);
+    }
+    state.alloc();
+    assert_eq!((COUNT as usize).encode(&mut state), Ok(()));
+    for i in 0..COUNT {
+        assert_eq!(i.encode(&mut state), Ok(()));
+    }
+
+    state.start = 0;
+    let sum: u64 = state
+        .decode_iter::<u32>()
+        .unwrap()
+        .map(|value| value.unwrap() as u64)
+        .sum();
+    assert_eq!(sum, (COUNT as u64 - 1) * COUNT as u64 / 2);
+    assert_eq!(state.start, state.end);
+}
+
+#[test]
+fn test_decode_iter_error() {
+    // 3 elements, the second one has an invalid prefix
+    let mut state = State::from_buffer(vec![3, 0xFE, 1, 0, 0, 0, 0xFD, 2, 0, 0, 0]);
+    let values: Vec<_> = state.decode_iter::<u32>().unwrap().collect();
+    assert_eq!(values, vec![Ok(1), Err(DecodeError::TypeMismatch)]);
+
+    let mut state = State::from_buffer(vec![4, 1, 2]);
+    assert!(state.decode_iter::<u8>().is_err());
+}
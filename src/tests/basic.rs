@@ -14,6 +14,7 @@ mod tests {
                 start: 0,
                 end: 0,
                 buffer: None,
+                ..Default::default()
             }
         );
     }
@@ -27,6 +28,7 @@ mod tests {
                 start: 0,
                 end: 0,
                 buffer: None,
+                ..Default::default()
             }
         );
         state.end = 5;
@@ -37,10 +39,36 @@ mod tests {
                 start: 0,
                 end: 5,
                 buffer: Some(vec![0, 0, 0, 0, 0]),
+                ..Default::default()
             }
         );
     }
 
+    #[test]
+    fn test_state_position_seek() {
+        let mut state = State::new();
+        42u8.pre_encode(&mut state);
+        4200u16.pre_encode(&mut state);
+        state.alloc();
+        assert_eq!(42u8.encode(&mut state), Ok(()));
+        assert_eq!(4200u16.encode(&mut state), Ok(()));
+        assert_eq!(state.position(), 4);
+
+        assert_eq!(state.seek(1), Ok(()));
+        assert_eq!(state.position(), 1);
+        assert_eq!(u16::decode(&mut state), Ok(4200));
+        // rewind and decode the same value again
+        assert_eq!(state.seek(1), Ok(()));
+        assert_eq!(u16::decode(&mut state), Ok(4200));
+        assert_eq!(state.position(), state.end);
+
+        assert_eq!(state.seek(0), Ok(()));
+        assert_eq!(u8::decode(&mut state), Ok(42));
+
+        assert_eq!(state.seek(5), Err(DecodeError::BufferTooSmall));
+        assert_eq!(state.position(), 1);
+    }
+
     #[test]
     fn test_zig_zag_encode() {
         assert_eq!(zig_zag_encode(0), 0);
@@ -54,6 +82,65 @@ mod tests {
         assert_eq!(zig_zag_encode(-4200), 8399);
     }
 
+    #[test]
+    fn test_zig_zag_decode() {
+        assert_eq!(zig_zag_decode(0), 0);
+        assert_eq!(zig_zag_decode(1), -1);
+        assert_eq!(zig_zag_decode(2), 1);
+        assert_eq!(zig_zag_decode(84), 42);
+        assert_eq!(zig_zag_decode(8399), -4200);
+        assert_eq!(zig_zag_decode(u64::MAX - 1), i64::MAX);
+        assert_eq!(zig_zag_decode(u64::MAX), i64::MIN);
+    }
+
+    #[test]
+    fn test_zig_zag_extremes() {
+        assert_eq!(zig_zag_encode(i64::MAX), u64::MAX - 1);
+        assert_eq!(zig_zag_encode(i64::MIN), u64::MAX);
+    }
+
+    #[test]
+    fn test_signed_unsigned_cross_decode() {
+        // the wire format does not encode signedness, decoding with the wrong type
+        // yields the zig-zag interpretation instead of an error
+        let mut state = State::from_buffer(encode_to_vec(&3u16).unwrap());
+        assert_eq!(i16::decode(&mut state), Ok(-2));
+
+        let mut state = State::from_buffer(encode_to_vec(&-2i16).unwrap());
+        assert_eq!(u16::decode(&mut state), Ok(3));
+
+        let mut state = State::from_buffer(encode_to_vec(&42u8).unwrap());
+        assert_eq!(i8::decode(&mut state), Ok(21));
+
+        let mut state = State::from_buffer(encode_to_vec(&-1i64).unwrap());
+        assert_eq!(u64::decode(&mut state), Ok(1));
+
+        // a different width is detected by the prefix
+        let mut state = State::from_buffer(encode_to_vec(&-2i32).unwrap());
+        assert_eq!(i16::decode(&mut state), Err(DecodeError::TypeMismatch));
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn test_isize() {
+        let mut state = State::new();
+        let values = [0isize, -1, 42, isize::MIN, isize::MAX];
+        for value in values.iter() {
+            value.pre_encode(&mut state);
+        }
+        assert_eq!(state.end, 1 + 1 + 1 + 9 + 9);
+        state.alloc();
+        for value in values.iter() {
+            assert_eq!(value.encode(&mut state), Ok(()));
+        }
+
+        state.start = 0;
+        for value in values.iter() {
+            assert_eq!(isize::decode(&mut state), Ok(*value));
+        }
+        assert_eq!(state.start, state.end);
+    }
+
     #[test]
     fn test_bool_pre_encode() {
         let mut state = State::new();
@@ -65,6 +152,7 @@ mod tests {
                 start: 0,
                 end: 1,
                 buffer: None,
+                ..Default::default()
             }
         );
         false.pre_encode(&mut state);
@@ -74,6 +162,7 @@ mod tests {
                 start: 0,
                 end: 2,
                 buffer: None,
+                ..Default::default()
             }
         );
     }
@@ -92,6 +181,7 @@ mod tests {
                 start: 0,
                 end: 2,
                 buffer: Some(vec![0, 0]),
+                ..Default::default()
             }
         );
 
@@ -102,6 +192,7 @@ mod tests {
                 start: 1,
                 end: 2,
                 buffer: Some(vec![1, 0]),
+                ..Default::default()
             }
         );
 
@@ -112,6 +203,7 @@ mod tests {
                 start: 2,
                 end: 2,
                 buffer: Some(vec![1, 0]),
+                ..Default::default()
             }
         );
     }
@@ -135,4 +227,34 @@ mod tests {
 
         assert_eq!(f32::decode(&mut state), Err(DecodeError::BufferTooSmall));
     }
+
+    #[test]
+    fn test_bool_decode_non_canonical() {
+        let mut state = State::from_buffer(vec![0, 1, 0xFF, 2]);
+        assert_eq!(bool::decode(&mut state), Ok(false));
+        assert_eq!(bool::decode(&mut state), Ok(true));
+        assert_eq!(bool::decode(&mut state), Err(DecodeError::TypeMismatch));
+        assert_eq!(bool::decode(&mut state), Err(DecodeError::TypeMismatch));
+    }
+
+    #[test]
+    fn test_char() {
+        assert_eq!(encode_to_vec(&'a'), Ok(vec![b'a']));
+        assert_eq!(char::decode(&mut State::from_buffer(vec![b'a'])), Ok('a'));
+
+        // U+1F33E does not fit into two bytes
+        let buffer = encode_to_vec(&'🌾').unwrap();
+        assert_eq!(buffer, vec![0xFE, 0x3E, 0xF3, 0x01, 0x00]);
+        assert_eq!(char::decode(&mut State::from_buffer(buffer)), Ok('🌾'));
+
+        // surrogate
+        let mut state = State::from_buffer(vec![0xFD, 0x00, 0xD8]);
+        assert_eq!(char::decode(&mut state), Err(DecodeError::TypeMismatch));
+        // beyond U+10FFFF
+        let mut state = State::from_buffer(vec![0xFE, 0x00, 0x00, 0x11, 0x00]);
+        assert_eq!(char::decode(&mut state), Err(DecodeError::TypeMismatch));
+        // beyond u32::MAX
+        let mut state = State::from_buffer(vec![0xFF, 0, 0, 0, 0, 1, 0, 0, 0]);
+        assert_eq!(char::decode(&mut state), Err(DecodeError::TypeMismatch));
+    }
 }
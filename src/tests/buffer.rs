@@ -17,6 +17,7 @@ fn test_buffer_pre_encode_empty() {
             start: 0,
             end: 1,
             buffer: None,
+            ..Default::default()
         }
     );
 }
@@ -33,6 +34,7 @@ fn test_buffer_pre_encode_short() {
             start: 0,
             end: 8,
             buffer: None,
+            ..Default::default()
         }
     );
 }
@@ -51,6 +53,7 @@ fn test_buffer_pre_encode_long() {
             start: 0,
             end: 3 + BUFFER_LONG_SIZE, // buffer length is encoded as u16, since size is larger than u8::MAX
             buffer: None,
+            ..Default::default()
         }
     );
 }
@@ -70,6 +73,7 @@ fn test_buffer_encode_empty() {
             start: 1,
             end: 1,
             buffer: Some(vec![0]),
+            ..Default::default()
         }
     );
 }
@@ -94,10 +98,30 @@ fn test_buffer_encode_short() {
             start: 8,
             end: 8,
             buffer: Some(expected_buffer),
+            ..Default::default()
         }
     );
 }
 
+#[test]
+fn test_buffer_encode_eq_bytes() {
+    let mut state = State::new();
+    Some(b"ab".as_slice()).pre_encode(&mut state);
+    None.pre_encode(&mut state);
+    state.alloc();
+    assert!(!state.eq_bytes(&[2, b'a', b'b', 0]));
+
+    assert_eq!(Some(b"ab".as_slice()).encode(&mut state), Ok(()));
+    assert_eq!(None.encode(&mut state), Ok(()));
+    // only the encoded bytes are compared, not the position
+    assert!(state.eq_bytes(&[2, b'a', b'b', 0]));
+    state.start = 0;
+    assert!(state.eq_bytes(&[2, b'a', b'b', 0]));
+    assert!(!state.eq_bytes(&[2, b'a', b'b']));
+
+    assert!(State::new().eq_bytes(&[]));
+}
+
 #[test]
 fn test_buffer_encode_long() {
     let mut state = State::new();
@@ -121,6 +145,7 @@ fn test_buffer_encode_long() {
             start: 3 + BUFFER_LONG_SIZE,
             end: 3 + BUFFER_LONG_SIZE,
             buffer: Some(expected_buffer),
+            ..Default::default()
         }
     );
 }
@@ -181,6 +206,153 @@ fn test_buffer_decode_long() {
     assert_eq!(state.start, state.end);
 }
 
+#[test]
+fn test_buffer_decode_vec_empty() {
+    let mut state = State::new();
+
+    None.pre_encode(&mut state);
+
+    state.alloc();
+
+    assert_eq!(None.encode(&mut state), Ok(()));
+
+    state.start = 0;
+    assert_eq!(Option::<Vec<u8>>::decode(&mut state), Ok(None));
+    assert_eq!(state.start, state.end);
+}
+
+#[test]
+fn test_buffer_decode_vec_short() {
+    let mut state = State::new();
+
+    let buffer = "content";
+
+    Some(buffer.as_bytes()).pre_encode(&mut state);
+
+    state.alloc();
+
+    assert_eq!(Some(buffer.as_bytes()).encode(&mut state), Ok(()));
+
+    state.start = 0;
+    assert_eq!(
+        Option::<Vec<u8>>::decode(&mut state),
+        Ok(Some(buffer.into()))
+    );
+    assert_eq!(state.start, state.end);
+}
+
+#[test]
+fn test_buffer_decode_vec_long() {
+    let mut state = State::new();
+
+    const BUFFER_LONG_SIZE: usize = u8::MAX as usize + 1;
+    let buffer = vec![3u8; BUFFER_LONG_SIZE];
+
+    Some(buffer.as_slice()).pre_encode(&mut state);
+
+    state.alloc();
+
+    assert_eq!(Some(buffer.as_slice()).encode(&mut state), Ok(()));
+
+    state.start = 0;
+    assert_eq!(Option::<Vec<u8>>::decode(&mut state), Ok(Some(buffer)));
+    assert_eq!(state.start, state.end);
+}
+
+#[test]
+fn test_vec_u8_matches_buffer_layout() {
+    let buffer: Vec<u8> = vec![0, 1, 0xFC, 0xFD, 0xFE, 0xFF];
+
+    let mut buffer_state = State::new();
+    Some(buffer.as_slice()).pre_encode(&mut buffer_state);
+    buffer_state.alloc();
+    assert_eq!(Some(buffer.as_slice()).encode(&mut buffer_state), Ok(()));
+
+    let mut vec_state = State::new();
+    buffer.pre_encode(&mut vec_state);
+    assert_eq!(vec_state.end, 1 + buffer.len());
+    vec_state.alloc();
+    assert_eq!(buffer.encode(&mut vec_state), Ok(()));
+    assert_eq!(vec_state, buffer_state);
+
+    vec_state.start = 0;
+    assert_eq!(Vec::<u8>::decode(&mut vec_state), Ok(buffer.clone()));
+    buffer_state.start = 0;
+    assert_eq!(
+        Option::<Box<Vec<u8>>>::decode(&mut buffer_state),
+        Ok(Some(Box::new(buffer)))
+    );
+}
+
+#[test]
+fn test_vec_u8_64k() {
+    let buffer: Vec<u8> = (0..64 * 1024).map(|i| i as u8).collect();
+
+    let mut state = State::new();
+    buffer.pre_encode(&mut state);
+    assert_eq!(state.end, 5 + buffer.len()); // length is encoded as u32
+    state.alloc();
+    assert_eq!(buffer.encode(&mut state), Ok(()));
+
+    state.start = 0;
+    assert_eq!(Vec::<u8>::decode(&mut state), Ok(buffer));
+    assert_eq!(state.start, state.end);
+}
+
+#[test]
+fn test_vec_of_buffers() {
+    let buffers: Vec<Vec<u8>> = vec![vec![], vec![0xFF], vec![1, 2, 3, 0xFD]];
+
+    let mut state = State::new();
+    buffers.pre_encode(&mut state);
+    state.alloc();
+    assert_eq!(buffers.encode(&mut state), Ok(()));
+    // count followed by each buffer with its length, no per-byte headers
+    assert_eq!(state.buffer, Some(vec![3, 0, 1, 0xFF, 4, 1, 2, 3, 0xFD]));
+
+    state.start = 0;
+    assert_eq!(Vec::<Vec<u8>>::decode(&mut state), Ok(buffers));
+    assert_eq!(state.start, state.end);
+}
+
+#[test]
+fn test_bytes_empty() {
+    let mut state = State::new();
+    let bytes = Bytes::default();
+
+    bytes.pre_encode(&mut state);
+    state.alloc();
+    assert_eq!(bytes.encode(&mut state), Ok(()));
+    assert_eq!(state.buffer, Some(vec![0]));
+
+    state.start = 0;
+    assert_eq!(Bytes::decode(&mut state), Ok(bytes));
+    assert_eq!(state.start, state.end);
+}
+
+#[test]
+fn test_bytes() {
+    let mut state = State::new();
+    let bytes = Bytes::from(b"content".to_vec());
+
+    bytes.pre_encode(&mut state);
+    state.alloc();
+    assert_eq!(bytes.encode(&mut state), Ok(()));
+
+    let mut buffer_state = State::new();
+    Some(b"content".as_slice()).pre_encode(&mut buffer_state);
+    buffer_state.alloc();
+    assert_eq!(
+        Some(b"content".as_slice()).encode(&mut buffer_state),
+        Ok(())
+    );
+    assert_eq!(state, buffer_state);
+
+    state.start = 0;
+    assert_eq!(Bytes::decode(&mut state), Ok(bytes));
+    assert_eq!(state.start, state.end);
+}
+
 //
 // raw
 //
@@ -196,6 +368,7 @@ fn test_raw_pre_encode_slice_empty() {
             start: 0,
             end: 0,
             buffer: None,
+            ..Default::default()
         }
     );
 }
@@ -211,6 +384,7 @@ fn test_raw_pre_encode_vec_ref_empty() {
             start: 0,
             end: 0,
             buffer: None,
+            ..Default::default()
         }
     );
 }
@@ -226,6 +400,7 @@ fn test_raw_pre_encode_vec_empty() {
             start: 0,
             end: 0,
             buffer: None,
+            ..Default::default()
         }
     );
 }
@@ -241,6 +416,7 @@ fn test_raw_pre_encode_slice_non_empty() {
             start: 0,
             end: 7,
             buffer: None,
+            ..Default::default()
         }
     );
 }
@@ -256,6 +432,7 @@ fn test_raw_pre_encode_vec_ref_non_empty() {
             start: 0,
             end: 7,
             buffer: None,
+            ..Default::default()
         }
     );
 }
@@ -271,6 +448,7 @@ fn test_raw_pre_encode_vec_non_empty() {
             start: 0,
             end: 7,
             buffer: None,
+            ..Default::default()
         }
     );
 }
@@ -291,6 +469,7 @@ fn test_raw_encode_slice_empty() {
             start: 0,
             end: 0,
             buffer: Some(vec![]),
+            ..Default::default()
         }
     );
 }
@@ -311,6 +490,7 @@ fn test_raw_encode_vec_ref_empty() {
             start: 0,
             end: 0,
             buffer: Some(vec![]),
+            ..Default::default()
         }
     );
 }
@@ -331,6 +511,7 @@ fn test_raw_encode_vec_empty() {
             start: 0,
             end: 0,
             buffer: Some(vec![]),
+            ..Default::default()
         }
     );
 }
@@ -350,6 +531,7 @@ fn test_raw_encode_vec_ref_non_empty() {
             start: 7,
             end: 7,
             buffer: Some(buffer),
+            ..Default::default()
         }
     );
 }
@@ -398,6 +580,7 @@ fn test_string_pre_encode_empty() {
             start: 0,
             end: 1,
             buffer: None,
+            ..Default::default()
         }
     );
 }
@@ -414,6 +597,7 @@ fn test_string_pre_encode_short() {
             start: 0,
             end: 8,
             buffer: None,
+            ..Default::default()
         }
     );
 }
@@ -433,6 +617,7 @@ fn test_string_encode_empty() {
             start: 1,
             end: 1,
             buffer: Some(vec![0]),
+            ..Default::default()
         }
     );
 }
@@ -454,6 +639,7 @@ fn test_string_encode_short() {
             start: 8,
             end: 8,
             buffer: Some("\x07content".as_bytes().to_vec()),
+            ..Default::default()
         }
     );
 }
@@ -489,3 +675,400 @@ fn test_string_decode_short() {
     assert_eq!(String::decode(&mut state), Ok("content".into()));
     assert_eq!(state.start, state.end);
 }
+
+#[test]
+fn test_string_decode_length_exceeds_buffer() {
+    let mut state = State::new();
+    // claim a string of u64::MAX bytes followed by only 2 bytes of content
+    u64::MAX.pre_encode(&mut state);
+    state.end += 2;
+    state.alloc();
+    assert_eq!(u64::MAX.encode(&mut state), Ok(()));
+    assert_eq!(state.write(b"hi"), Ok(()));
+
+    state.start = 0;
+    assert_eq!(
+        String::decode(&mut state),
+        Err(DecodeError::InvalidLength {
+            declared: u64::MAX as usize,
+            available: 2
+        })
+    );
+
+    state.start = 0;
+    assert_eq!(
+        Vec::<u8>::decode(&mut state),
+        Err(DecodeError::InvalidLength {
+            declared: u64::MAX as usize,
+            available: 2
+        })
+    );
+}
+
+#[test]
+fn test_string_decode_too_large() {
+    let mut state = State::new();
+    let size = MAX_STRING_DECODE_SIZE + 1;
+    size.pre_encode(&mut state);
+    state.end += size;
+    state.alloc();
+    assert_eq!(size.encode(&mut state), Ok(()));
+
+    state.start = 0;
+    assert_eq!(String::decode(&mut state), Err(DecodeError::StringTooLarge));
+}
+
+#[test]
+fn test_string_decode_in_place() {
+    let mut state = State::new();
+    "hello".pre_encode(&mut state);
+    "".pre_encode(&mut state);
+    state.alloc();
+    assert_eq!("hello".encode(&mut state), Ok(()));
+    assert_eq!("".encode(&mut state), Ok(()));
+
+    let mut target = String::with_capacity(64);
+    target.push_str("previous content");
+    let pointer = target.as_ptr();
+
+    state.start = 0;
+    assert_eq!(String::decode_in_place(&mut state, &mut target), Ok(()));
+    assert_eq!(target, "hello");
+    assert_eq!(target.capacity(), 64);
+    assert_eq!(target.as_ptr(), pointer);
+
+    assert_eq!(String::decode_in_place(&mut state, &mut target), Ok(()));
+    assert_eq!(target, "");
+    assert_eq!(target.capacity(), 64);
+    assert_eq!(state.start, state.end);
+}
+
+#[test]
+fn test_path() {
+    use std::path::{Path, PathBuf};
+
+    let path = Path::new("dir/file.txt");
+    let mut state = State::new();
+    path.pre_encode(&mut state);
+    state.alloc();
+    assert_eq!(path.encode(&mut state), Ok(()));
+    // same layout as a string
+    assert_eq!(state.buffer, encode_to_vec(&"dir/file.txt").ok());
+
+    state.start = 0;
+    assert_eq!(PathBuf::decode(&mut state), Ok(path.to_path_buf()));
+    assert_eq!(state.start, state.end);
+
+    let path_buf = PathBuf::from("relative").join("path");
+    let buffer = encode_to_vec(&path_buf).unwrap();
+    assert_eq!(
+        PathBuf::decode(&mut State::from_buffer(buffer)),
+        Ok(path_buf)
+    );
+}
+
+#[test]
+fn test_vec_decode_in_place() {
+    let mut state = State::new();
+    let value = vec![1u32, 2, 3];
+    let bytes = vec![0u8, 0xFF];
+    value.pre_encode(&mut state);
+    bytes.pre_encode(&mut state);
+    state.alloc();
+    assert_eq!(value.encode(&mut state), Ok(()));
+    assert_eq!(bytes.encode(&mut state), Ok(()));
+
+    let mut target: Vec<u32> = Vec::with_capacity(8);
+    target.push(42);
+    let pointer = target.as_ptr();
+    let mut bytes_target: Vec<u8> = vec![7; 16];
+
+    state.start = 0;
+    assert_eq!(Vec::<u32>::decode_in_place(&mut state, &mut target), Ok(()));
+    assert_eq!(target, value);
+    assert_eq!(target.capacity(), 8);
+    assert_eq!(target.as_ptr(), pointer);
+
+    assert_eq!(
+        Vec::<u8>::decode_in_place(&mut state, &mut bytes_target),
+        Ok(())
+    );
+    assert_eq!(bytes_target, bytes);
+    assert_eq!(bytes_target.capacity(), 16);
+    assert_eq!(state.start, state.end);
+}
+
+//
+// fixed
+//
+
+#[test]
+fn test_fixed_decode_buffer_too_small() {
+    let mut state = State::new();
+    state.end = 10;
+    state.alloc();
+
+    assert_eq!(
+        Fixed32::decode(&mut state),
+        Err(DecodeError::BufferTooSmall)
+    );
+    assert_eq!(state.start, 0);
+}
+
+#[test]
+fn test_decode_fixed_ref() {
+    let mut state = State::from_buffer((0..40).collect());
+    state.start = 2;
+    let expected = state.buffer.as_ref().unwrap()[2..].as_ptr();
+
+    let fixed: &[u8; 32] = state.decode_fixed_ref().unwrap();
+    assert_eq!(fixed.len(), 32);
+    assert_eq!(fixed.as_ptr(), expected);
+    assert_eq!(fixed[0], 2);
+    assert_eq!(fixed[31], 33);
+    assert_eq!(state.start, 34);
+
+    assert_eq!(
+        state.decode_fixed_ref::<8>(),
+        Err(DecodeError::BufferTooSmall)
+    );
+}
+
+#[test]
+fn test_fixed_dyn() {
+    let mut state = State::new();
+    let fixed = FixedDyn::from((0..48).collect::<Vec<u8>>());
+    assert_eq!(fixed.len(), 48);
+
+    fixed.pre_encode(&mut state);
+    42u8.pre_encode(&mut state);
+    assert_eq!(state.end, 49);
+    state.alloc();
+    assert_eq!(fixed.encode(&mut state), Ok(()));
+    assert_eq!(42u8.encode(&mut state), Ok(()));
+
+    state.start = 0;
+    assert_eq!(FixedDyn::decode_len(&mut state, 48), Ok(fixed));
+    assert_eq!(u8::decode(&mut state), Ok(42));
+
+    state.start = 2;
+    assert_eq!(
+        FixedDyn::decode_len(&mut state, 48),
+        Err(DecodeError::BufferTooSmall)
+    );
+    assert_eq!(
+        FixedDyn::decode_len(&mut state, 0),
+        Ok(FixedDyn::from(vec![]))
+    );
+}
+
+//
+// fixed width integers
+//
+
+#[test]
+fn test_fixed_u32() {
+    for value in [0, 1, 0xFC, 0xFD, 0x1234_5678, u32::MAX] {
+        let fixed = FixedU32(value);
+        assert_eq!(encoded_size(&fixed), 4);
+        let buffer = encode_to_vec(&fixed).unwrap();
+        assert_eq!(buffer, value.to_le_bytes());
+        assert_eq!(FixedU32::decode(&mut State::from_buffer(buffer)), Ok(fixed));
+    }
+    assert_eq!(
+        FixedU32::decode(&mut State::from_buffer(vec![1, 2, 3])),
+        Err(DecodeError::BufferTooSmall)
+    );
+}
+
+#[test]
+fn test_fixed_u64() {
+    for value in [0, 1, 0xFC, 0xFFFF_FFFF, 1_700_000_000_000, u64::MAX] {
+        let fixed = FixedU64(value);
+        assert_eq!(encoded_size(&fixed), 8);
+        let buffer = encode_to_vec(&fixed).unwrap();
+        assert_eq!(buffer, value.to_le_bytes());
+        assert_eq!(FixedU64::decode(&mut State::from_buffer(buffer)), Ok(fixed));
+    }
+}
+
+#[test]
+fn test_u32_array_bulk_encode() {
+    let values: Vec<u32> = (0..1000u32).map(|i| i.wrapping_mul(0x0301_0701)).collect();
+    for byte_order in [ByteOrder::LittleEndian, ByteOrder::BigEndian] {
+        let mut state = State::new();
+        state.set_byte_order(byte_order);
+        let array = U32Array::Slice(&values);
+        array.pre_encode(&mut state);
+        state.alloc();
+        assert_eq!(array.encode(&mut state), Ok(()));
+
+        // compare with the per-element slow path
+        let mut expected = State::new();
+        expected.set_byte_order(byte_order);
+        expected.end = state.end;
+        expected.alloc();
+        assert_eq!(values.len().encode(&mut expected), Ok(()));
+        assert_eq!(encode_u32_each(&values, &mut expected), Ok(()));
+        assert_eq!(state, expected);
+
+        state.start = 0;
+        assert_eq!(
+            U32Array::decode(&mut state),
+            Ok(U32Array::Vec(values.clone()))
+        );
+    }
+
+    // the bulk path matches the slow path in host byte order
+    let mut bulk = State::new();
+    bulk.end = 4 * values.len();
+    bulk.alloc();
+    let mut each = State::new();
+    each.end = 4 * values.len();
+    each.alloc();
+    assert_eq!(encode_u32_bulk(&values, &mut bulk), Ok(()));
+    assert_eq!(encode_u32_each(&values, &mut each), Ok(()));
+    if cfg!(target_endian = "little") {
+        assert_eq!(bulk, each);
+    }
+}
+
+#[test]
+fn test_fixed_width_big_endian() {
+    let mut state = State::new_be();
+    let array = U32Array::Slice(&[1, 0x0102_0304]);
+    array.pre_encode(&mut state);
+    FixedU32(0x0A0B_0C0D).pre_encode(&mut state);
+    FixedU64(1).pre_encode(&mut state);
+    // the compact integer encoding is not affected by the byte order
+    300u32.pre_encode(&mut state);
+    state.alloc();
+    assert_eq!(array.encode(&mut state), Ok(()));
+    assert_eq!(FixedU32(0x0A0B_0C0D).encode(&mut state), Ok(()));
+    assert_eq!(FixedU64(1).encode(&mut state), Ok(()));
+    assert_eq!(300u32.encode(&mut state), Ok(()));
+    assert_eq!(
+        state.buffer,
+        Some(vec![
+            2, 0, 0, 0, 1, 1, 2, 3, 4, // U32Array
+            0x0A, 0x0B, 0x0C, 0x0D, // FixedU32
+            0, 0, 0, 0, 0, 0, 0, 1, // FixedU64
+            0xFE, 0x2C, 0x01, 0, 0, // u32
+        ])
+    );
+
+    state.start = 0;
+    assert_eq!(
+        U32Array::decode(&mut state),
+        Ok(U32Array::Vec(vec![1, 0x0102_0304]))
+    );
+    assert_eq!(FixedU32::decode(&mut state), Ok(FixedU32(0x0A0B_0C0D)));
+    assert_eq!(FixedU64::decode(&mut state), Ok(FixedU64(1)));
+    assert_eq!(u32::decode(&mut state), Ok(300));
+    assert_eq!(state.start, state.end);
+}
+
+#[test]
+fn test_fixed_width_record() {
+    // fixed width integers interleaved with compact types
+    let mut state = State::new();
+    FixedU64(1_700_000_000_000).pre_encode(&mut state);
+    "id".pre_encode(&mut state);
+    FixedU32(7).pre_encode(&mut state);
+    state.alloc();
+    assert_eq!(FixedU64(1_700_000_000_000).encode(&mut state), Ok(()));
+    assert_eq!("id".encode(&mut state), Ok(()));
+    assert_eq!(FixedU32(7).encode(&mut state), Ok(()));
+    assert_eq!(state.end, 8 + 3 + 4);
+
+    state.start = 0;
+    assert_eq!(
+        FixedU64::decode(&mut state),
+        Ok(FixedU64(1_700_000_000_000))
+    );
+    assert_eq!(String::decode(&mut state), Ok("id".to_string()));
+    assert_eq!(FixedU32::decode(&mut state), Ok(FixedU32(7)));
+    assert_eq!(state.start, state.end);
+}
+
+//
+// fixed width length prefix
+//
+
+#[test]
+fn test_length_prefixed_u16() {
+    let mut state = State::new();
+    let value = LengthPrefixed::<_, 2>(b"hello".as_slice());
+    value.pre_encode(&mut state);
+    assert_eq!(state.end, 7);
+    state.alloc();
+    assert_eq!(value.encode(&mut state), Ok(()));
+    assert_eq!(state.buffer, Some(vec![5, 0, b'h', b'e', b'l', b'l', b'o']));
+
+    state.start = 0;
+    assert_eq!(
+        LengthPrefixed::<Vec<u8>, 2>::decode(&mut state),
+        Ok(LengthPrefixed(b"hello".to_vec()))
+    );
+    assert_eq!(state.start, state.end);
+}
+
+#[test]
+fn test_length_prefixed_u32() {
+    let mut state = State::new();
+    let buffer = vec![7u8; 300];
+    let value = LengthPrefixed::<_, 4>(&buffer);
+    value.pre_encode(&mut state);
+    assert_eq!(state.end, 304);
+    state.alloc();
+    assert_eq!(value.encode(&mut state), Ok(()));
+    assert_eq!(state.buffer.as_ref().unwrap()[..5], [0x2C, 0x01, 0, 0, 7]);
+
+    state.start = 0;
+    assert_eq!(
+        LengthPrefixed::<Vec<u8>, 4>::decode(&mut state),
+        Ok(LengthPrefixed(buffer))
+    );
+    assert_eq!(state.start, state.end);
+
+    // empty buffer
+    let mut state = State::new();
+    let value = LengthPrefixed::<Vec<u8>, 4>(vec![]);
+    value.pre_encode(&mut state);
+    state.alloc();
+    assert_eq!(value.encode(&mut state), Ok(()));
+    assert_eq!(state.buffer, Some(vec![0, 0, 0, 0]));
+    state.start = 0;
+    assert_eq!(LengthPrefixed::<Vec<u8>, 4>::decode(&mut state), Ok(value));
+}
+
+#[test]
+fn test_length_prefixed_errors() {
+    let mut state = State::new();
+    let buffer = vec![0u8; 256];
+    let value = LengthPrefixed::<_, 1>(&buffer);
+    value.pre_encode(&mut state);
+    state.alloc();
+    assert_eq!(value.encode(&mut state), Err(EncodeError::LengthTooLarge));
+
+    let mut state = State {
+        start: 0,
+        end: 4,
+        buffer: Some(vec![5, 0, b'h', b'i']),
+        ..Default::default()
+    };
+    assert_eq!(
+        LengthPrefixed::<Vec<u8>, 2>::decode(&mut state),
+        Err(DecodeError::InvalidLength {
+            declared: 5,
+            available: 2
+        })
+    );
+
+    // truncated length itself
+    let mut state = State::from_buffer(vec![5]);
+    assert_eq!(
+        LengthPrefixed::<Vec<u8>, 2>::decode(&mut state),
+        Err(DecodeError::BufferTooSmall)
+    );
+}
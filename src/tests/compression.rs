@@ -0,0 +1,57 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+
+use crate::compression::*;
+use crate::*;
+
+fn payload() -> Vec<String> {
+    vec![String::from("compact-encoding"); 200]
+}
+
+#[test]
+fn test_compression_none() {
+    let value = payload();
+    let compressed = encode_compressed(&value, Compression::None).unwrap();
+    assert_eq!(compressed[0], 0);
+    assert!(compressed.len() > encoded_size(&value));
+    assert_eq!(decode_compressed::<Vec<String>>(&compressed), Ok(value));
+}
+
+#[cfg(feature = "deflate")]
+#[test]
+fn test_compression_deflate() {
+    let value = payload();
+    let compressed = encode_compressed(&value, Compression::Deflate).unwrap();
+    assert_eq!(compressed[0], 1);
+    assert!(compressed.len() < encoded_size(&value) / 10);
+    assert_eq!(decode_compressed::<Vec<String>>(&compressed), Ok(value));
+}
+
+#[cfg(feature = "lz4")]
+#[test]
+fn test_compression_lz4() {
+    let value = payload();
+    let compressed = encode_compressed(&value, Compression::Lz4).unwrap();
+    assert_eq!(compressed[0], 2);
+    assert!(compressed.len() < encoded_size(&value) / 10);
+    assert_eq!(decode_compressed::<Vec<String>>(&compressed), Ok(value));
+}
+
+#[test]
+fn test_compression_invalid() {
+    // unknown codec tag
+    let mut compressed = encode_compressed(&42u64, Compression::None).unwrap();
+    compressed[0] = 7;
+    assert_eq!(
+        decode_compressed::<u64>(&compressed),
+        Err(DecodeError::TypeMismatch)
+    );
+
+    // uncompressed length does not match
+    let mut compressed = encode_compressed(&42u64, Compression::None).unwrap();
+    compressed[1] = 8;
+    assert_eq!(
+        decode_compressed::<u64>(&compressed),
+        Err(DecodeError::TypeMismatch)
+    );
+}
@@ -0,0 +1,118 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+
+use crate::*;
+
+trait Shape: EncodeDyn {
+    fn area(&self) -> u64;
+}
+
+#[derive(Debug, PartialEq)]
+struct Square {
+    side: u64,
+}
+
+impl TypeTag for Square {
+    const TYPE_ID: u32 = 1;
+}
+
+impl Encode for Square {
+    fn pre_encode(&self, state: &mut State) {
+        self.side.pre_encode(state);
+    }
+
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        self.side.encode(state)
+    }
+}
+
+impl Shape for Square {
+    fn area(&self) -> u64 {
+        self.side * self.side
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Rectangle {
+    width: u64,
+    height: u64,
+}
+
+impl TypeTag for Rectangle {
+    const TYPE_ID: u32 = 300;
+}
+
+impl Encode for Rectangle {
+    fn pre_encode(&self, state: &mut State) {
+        self.width.pre_encode(state);
+        self.height.pre_encode(state);
+    }
+
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        self.width.encode(state)?;
+        self.height.encode(state)
+    }
+}
+
+impl Shape for Rectangle {
+    fn area(&self) -> u64 {
+        self.width * self.height
+    }
+}
+
+fn registry() -> DynRegistry<dyn Shape> {
+    let mut registry = DynRegistry::<dyn Shape>::new();
+    registry.register::<Square>(|state| {
+        Ok(Box::new(Square {
+            side: u64::decode(state)?,
+        }))
+    });
+    registry.register::<Rectangle>(|state| {
+        Ok(Box::new(Rectangle {
+            width: u64::decode(state)?,
+            height: u64::decode(state)?,
+        }))
+    });
+    registry
+}
+
+#[test]
+fn test_dyn_roundtrip() {
+    let shapes: Vec<Box<dyn Shape>> = vec![
+        Box::new(Square { side: 3 }),
+        Box::new(Rectangle {
+            width: 2,
+            height: 5,
+        }),
+    ];
+
+    let mut state = State::new();
+    for shape in &shapes {
+        let shape: &dyn EncodeDyn = shape.as_ref();
+        state.encode_append(shape).unwrap();
+    }
+    // type id of Square, followed by its side
+    assert_eq!(state.buffer.as_ref().unwrap()[0], 1);
+
+    state.start = 0;
+    let registry = registry();
+    let decoded: Vec<u64> = (0..shapes.len())
+        .map(|_| registry.decode(&mut state).unwrap().area())
+        .collect();
+    assert_eq!(decoded, vec![9, 10]);
+    assert_eq!(state.start, state.end);
+}
+
+#[test]
+fn test_dyn_unregistered_type() {
+    let shape: &dyn EncodeDyn = &Rectangle {
+        width: 1,
+        height: 1,
+    };
+    let mut state = State::from_buffer(encode_to_vec(shape).unwrap());
+    let registry = DynRegistry::<dyn Shape>::new();
+    assert_eq!(
+        registry.decode(&mut state).map(|shape| shape.area()),
+        Err(DecodeError::TypeMismatch)
+    );
+}
@@ -0,0 +1,113 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+
+use crate::event_log::*;
+use crate::*;
+
+#[test]
+fn test_log_roundtrip() {
+    let entries = vec![
+        LogEntry {
+            timestamp: 1_700_000_000_000,
+            level: 2,
+            message: "started".into(),
+        },
+        LogEntry {
+            timestamp: 1_700_000_000_040,
+            level: 3,
+            message: "connected".into(),
+        },
+        LogEntry {
+            timestamp: 1_700_000_000_040,
+            level: 1,
+            message: String::new(),
+        },
+    ];
+
+    let mut writer = LogWriter::new();
+    for entry in &entries {
+        writer.write(entry).unwrap();
+    }
+    let buffer = writer.finish();
+    // the first delta is absolute, further deltas fit into a single byte
+    assert_eq!(
+        buffer.len(),
+        encoded_size(&(2 * 1_700_000_000_000usize)) + 1 + 8 + 1 + 1 + 10 + 1 + 1 + 1
+    );
+
+    let mut state = State::from_buffer(buffer);
+    let mut reader = LogReader::new();
+    for entry in &entries {
+        assert_eq!(reader.read(&mut state).as_ref(), Ok(entry));
+    }
+    assert_eq!(state.start, state.end);
+}
+
+#[test]
+fn test_log_timestamp_backwards() {
+    let entries = [u64::MAX, 0, 5, 3].map(|timestamp| LogEntry {
+        timestamp,
+        ..Default::default()
+    });
+
+    let mut writer = LogWriter::new();
+    for entry in &entries {
+        writer.write(entry).unwrap();
+    }
+
+    let mut state = State::from_buffer(writer.finish());
+    let mut reader = LogReader::new();
+    let timestamps: Vec<u64> = (0..entries.len())
+        .map(|_| reader.read(&mut state).unwrap().timestamp)
+        .collect();
+    assert_eq!(timestamps, vec![u64::MAX, 0, 5, 3]);
+}
+
+#[test]
+fn test_log_level_raw_byte() {
+    let entry = LogEntry {
+        timestamp: 1,
+        level: u8::MAX,
+        message: "x".into(),
+    };
+    let mut writer = LogWriter::new();
+    writer.write(&entry).unwrap();
+    let buffer = writer.finish();
+    // delta, level without prefix, message
+    assert_eq!(buffer, vec![2, 0xFF, 1, b'x']);
+    assert_eq!(
+        LogReader::new().read(&mut State::from_buffer(buffer)),
+        Ok(entry)
+    );
+}
+
+#[test]
+fn test_log_record() {
+    let record = LogRecord {
+        delta: -3,
+        level: 4,
+        message: "late".into(),
+    };
+    let buffer = encode_to_vec_checked(&record).unwrap();
+    assert_eq!(buffer, vec![5, 4, 4, b'l', b'a', b't', b'e']);
+    assert_eq!(
+        LogRecord::decode(&mut State::from_buffer(buffer)),
+        Ok(record)
+    );
+
+    // LogWriter encodes the delta form of each entry
+    let mut writer = LogWriter::new();
+    writer
+        .write(&LogEntry {
+            timestamp: 300,
+            level: 1,
+            message: "a".into(),
+        })
+        .unwrap();
+    let expected = LogRecord {
+        delta: 300,
+        level: 1,
+        message: "a".into(),
+    };
+    assert_eq!(Ok(writer.finish()), encode_to_vec(&expected));
+}
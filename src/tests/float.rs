@@ -17,6 +17,7 @@ mod tests {
                 start: 0,
                 end: 4,
                 buffer: None,
+                ..Default::default()
             }
         );
 
@@ -27,6 +28,7 @@ mod tests {
                 start: 0,
                 end: 4,
                 buffer: Some(vec![0, 0, 0, 0]),
+                ..Default::default()
             }
         );
 
@@ -38,6 +40,7 @@ mod tests {
                 end: 4,
                 // TODO double check expected value
                 buffer: Some(vec![0xDC, 0x3C, 0x22, 0x43]),
+                ..Default::default()
             }
         );
 
@@ -47,4 +50,56 @@ mod tests {
 
         assert_eq!(f32::decode(&mut state), Err(DecodeError::BufferTooSmall));
     }
+
+    #[test]
+    fn test_float_big_endian() {
+        let mut state = State::new_be();
+        const NUM32: f32 = 162.237_73;
+        const NUM64: f64 = 162.2377294;
+
+        NUM32.pre_encode(&mut state);
+        NUM64.pre_encode(&mut state);
+        state.alloc();
+        assert_eq!(NUM32.encode(&mut state), Ok(()));
+        assert_eq!(NUM64.encode(&mut state), Ok(()));
+        let mut expected = NUM32.to_be_bytes().to_vec();
+        expected.extend_from_slice(&NUM64.to_be_bytes());
+        assert_eq!(state.buffer, Some(expected));
+
+        state.start = 0;
+        assert_eq!(f32::decode(&mut state), Ok(NUM32));
+        assert_eq!(f64::decode(&mut state), Ok(NUM64));
+        assert_eq!(state.start, state.end);
+
+        // decoding with the wrong byte order does not fail, but returns a different value
+        state.start = 0;
+        state.set_byte_order(ByteOrder::LittleEndian);
+        assert_ne!(f32::decode(&mut state), Ok(NUM32));
+    }
+
+    #[test]
+    fn test_quantized_vec() {
+        let values: Vec<f32> = (0..384).map(|i| ((i as f32) * 0.37).sin() * 0.8).collect();
+        let quantized = QuantizedVec::from_f32_slice(&values);
+
+        let buffer = encode_to_vec(&quantized).unwrap();
+        // scale + length + one byte per value, instead of 4 bytes per f32
+        assert_eq!(buffer.len(), 4 + 3 + values.len());
+        assert!(buffer.len() < encoded_size(&values) / 3);
+
+        let mut state = State::from_buffer(buffer);
+        let decoded = QuantizedVec::decode(&mut state).unwrap();
+        assert_eq!(state.start, state.end);
+        assert_eq!(decoded, quantized);
+
+        let bound = decoded.scale / 2.0 + f32::EPSILON;
+        for (original, approximated) in values.iter().zip(decoded.to_f32_vec()) {
+            assert!((original - approximated).abs() <= bound);
+        }
+
+        let zeros = QuantizedVec::from_f32_slice(&[0.0; 3]);
+        assert_eq!(zeros.to_f32_vec(), vec![0.0; 3]);
+        let mut state = State::from_buffer(encode_to_vec(&zeros).unwrap());
+        assert_eq!(QuantizedVec::decode(&mut state), Ok(zeros));
+    }
 }
@@ -0,0 +1,469 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+
+use crate::*;
+
+#[test]
+fn test_encoded_size() {
+    assert_eq!(encoded_size(&42u8), 1);
+    assert_eq!(encoded_size(&4200u16), 3);
+    assert_eq!(encoded_size(&"hello"), 6);
+    assert_eq!(
+        encoded_size(&"hello"),
+        encode_to_vec(&"hello").unwrap().len()
+    );
+}
+
+#[test]
+fn test_encode_to_vec() {
+    assert_eq!(
+        encode_to_vec(&"hello"),
+        Ok(vec![5, b'h', b'e', b'l', b'l', b'o'])
+    );
+    assert_eq!(encode_to_vec(&4200u16), Ok(vec![0xFD, 0x68, 0x10]));
+}
+
+/// custom type which allocates `allocated` bytes, but writes `written` bytes
+struct Mismatched {
+    allocated: usize,
+    written: usize,
+}
+
+impl Encode for Mismatched {
+    fn pre_encode(&self, state: &mut State) {
+        state.end += self.allocated;
+    }
+
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        state.write(&vec![0; self.written])
+    }
+}
+
+#[test]
+fn test_encode_to_vec_checked() {
+    assert_eq!(encode_to_vec_checked(&"hi"), Ok(vec![2, b'h', b'i']));
+
+    let under_write = Mismatched {
+        allocated: 4,
+        written: 3,
+    };
+    assert_eq!(encode_to_vec(&under_write), Ok(vec![0; 4]));
+    assert_eq!(
+        encode_to_vec_checked(&under_write),
+        Err(EncodeError::IncompleteEncode)
+    );
+
+    let over_write = Mismatched {
+        allocated: 3,
+        written: 4,
+    };
+    assert_eq!(
+        encode_to_vec_checked(&over_write),
+        Err(EncodeError::BufferTooSmall)
+    );
+}
+
+#[test]
+fn test_validate_encode() {
+    assert_eq!(State::validate_encode(&"hi"), Ok(()));
+    assert_eq!(State::validate_encode(&vec![1u64, 2, u64::MAX]), Ok(()));
+    assert_eq!(
+        State::validate_encode(&Mismatched {
+            allocated: 2,
+            written: 2
+        }),
+        Ok(())
+    );
+    assert_eq!(
+        State::validate_encode(&Mismatched {
+            allocated: 3,
+            written: 2
+        }),
+        Err(EncodeError::IncompleteEncode)
+    );
+    assert_eq!(
+        State::validate_encode(&Mismatched {
+            allocated: 2,
+            written: 3
+        }),
+        Err(EncodeError::BufferTooSmall)
+    );
+}
+
+/// custom type with variable length content, which only estimates its size
+struct Message {
+    parts: Vec<String>,
+}
+
+impl SizeHint for Message {
+    fn size_hint(&self) -> usize {
+        // undercounts the length prefixes
+        self.parts.iter().map(String::len).sum()
+    }
+}
+
+impl Encode for Message {
+    /// inexact, encode_to_vec_with_hint() grows the buffer instead
+    fn pre_encode(&self, state: &mut State) {
+        state.end += self.size_hint();
+    }
+
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        self.parts.encode(state)
+    }
+}
+
+#[test]
+fn test_encode_to_vec_with_hint() {
+    let message = Message {
+        parts: vec!["ab".into(), "".into(), "cde".into()],
+    };
+    assert_eq!(message.size_hint(), 5);
+    let buffer = encode_to_vec_with_hint(&message).unwrap();
+    assert_eq!(buffer, vec![3, 2, b'a', b'b', 0, 3, b'c', b'd', b'e']);
+    assert_eq!(
+        Vec::<String>::decode(&mut State::from_buffer(buffer)),
+        Ok(message.parts)
+    );
+}
+
+#[test]
+fn test_encode_into_vec() {
+    let mut out = vec![0xAA];
+    assert_eq!(encode_into_vec(&4200u16, &mut out), Ok(()));
+    assert_eq!(encode_into_vec(&"hi", &mut out), Ok(()));
+    assert_eq!(out, vec![0xAA, 0xFD, 0x68, 0x10, 2, b'h', b'i']);
+
+    let mut state = State::from_buffer(out);
+    state.start = 1;
+    assert_eq!(u16::decode(&mut state), Ok(4200));
+    assert_eq!(String::decode(&mut state), Ok("hi".into()));
+    assert_eq!(state.start, state.end);
+
+    // existing content is kept if encoding fails
+    let mut out = vec![0xAA];
+    let value = Mismatched {
+        allocated: 2,
+        written: 3,
+    };
+    assert_eq!(
+        encode_into_vec(&value, &mut out),
+        Err(EncodeError::BufferTooSmall)
+    );
+    assert_eq!(out, vec![0xAA]);
+}
+
+#[test]
+fn test_vec_writer() {
+    let value = String::from("hello");
+    let mut vec: Vec<u8> = vec![];
+    assert_eq!(encode_to_writer(&value, &mut vec), Ok(()));
+    assert_eq!(Ok(vec), encode_to_vec(&value));
+
+    let mut vec: Vec<u8> = vec![];
+    assert_eq!(encode_to_writer(&u64::MAX, &mut vec), Ok(()));
+    assert_eq!(Ok(vec), encode_to_vec(&u64::MAX));
+}
+
+#[test]
+fn test_counting_writer() {
+    let mut writer = CountingWriter::new();
+    assert_eq!(encode_to_writer("hello", &mut writer), Ok(()));
+    assert_eq!(writer.count, encoded_size(&"hello"));
+
+    let value = vec![String::from("a"), String::from("bc")];
+    let mut writer = CountingWriter::new();
+    assert_eq!(encode_to_writer(&value, &mut writer), Ok(()));
+    assert_eq!(writer.count, encoded_size(&value));
+
+    let mut writer = CountingWriter::new();
+    assert_eq!(encode_to_writer(&u64::MAX, &mut writer), Ok(()));
+    assert_eq!(writer.count, encoded_size(&u64::MAX));
+}
+
+/// custom type with a faulty encode(), which never fits into the buffer
+struct NeverFits;
+
+impl SizeHint for NeverFits {
+    fn size_hint(&self) -> usize {
+        1
+    }
+}
+
+impl Encode for NeverFits {
+    fn pre_encode(&self, state: &mut State) {
+        state.end += 1;
+    }
+
+    fn encode(&self, _state: &mut State) -> EncodeResult {
+        Err(EncodeError::BufferTooSmall)
+    }
+}
+
+#[test]
+fn test_encode_to_vec_with_hint_growth_limit() {
+    assert_eq!(
+        encode_to_vec_with_hint(&NeverFits),
+        Err(EncodeError::BufferTooSmall)
+    );
+    let mut writer = CountingWriter::new();
+    assert_eq!(
+        encode_to_writer(&NeverFits, &mut writer),
+        Err(EncodeError::BufferTooSmall)
+    );
+    assert_eq!(writer.count, 0);
+}
+
+#[test]
+fn test_counting_writer_without_pre_encode() {
+    // Message only estimates its size in pre_encode(), the temporary buffer grows instead
+    let message = Message {
+        parts: vec!["a".repeat(40), "b".into()],
+    };
+    let mut writer = CountingWriter::new();
+    assert_eq!(encode_to_writer(&message, &mut writer), Ok(()));
+    assert_eq!(writer.count, 1 + 1 + 40 + 1 + 1);
+}
+
+#[test]
+fn test_encode_trait_object() {
+    let list = vec![1u32];
+    let values: Vec<&dyn Encode> = vec![&42u8, &"hi", &list];
+    let mut state = State::new();
+    for value in &values {
+        value.pre_encode(&mut state);
+    }
+    state.alloc();
+    for value in &values {
+        assert_eq!(value.encode(&mut state), Ok(()));
+    }
+    assert!(state.eq_bytes(&[42, 2, b'h', b'i', 1, 0xFE, 1, 0, 0, 0]));
+}
+
+#[test]
+fn test_hashing_writer() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::Hasher;
+
+    let value = vec![String::from("hash"), String::from("me")];
+
+    let mut writer = HashingWriter::new(DefaultHasher::new());
+    assert_eq!(encode_to_writer(&value, &mut writer), Ok(()));
+
+    let mut hasher = DefaultHasher::new();
+    hasher.write(&encode_to_vec(&value).unwrap());
+    assert_eq!(writer.finish(), hasher.finish());
+
+    // hashing the output of encode_to_vec() directly
+    let mut writer = HashingWriter::new(DefaultHasher::new());
+    assert_eq!(writer.write(&encode_to_vec(&value).unwrap()), Ok(()));
+    assert_eq!(writer.finish(), hasher.finish());
+}
+
+#[test]
+fn test_encode_with_header() {
+    const MAGIC: [u8; 4] = *b"CENC";
+    let buffer = encode_with_header(MAGIC, 2, &"payload").unwrap();
+    assert_eq!(buffer[..7], [b'C', b'E', b'N', b'C', 0xFD, 2, 0]);
+    assert_eq!(
+        decode_with_header::<String>(MAGIC, &buffer),
+        Ok((2, "payload".into()))
+    );
+}
+
+#[test]
+fn test_decode_with_header_bad_magic() {
+    let mut buffer = encode_with_header(*b"CENC", 1, &42u32).unwrap();
+    buffer[1] = b'X';
+    assert_eq!(
+        decode_with_header::<u32>(*b"CENC", &buffer),
+        Err(DecodeError::BadMagic)
+    );
+    assert_eq!(
+        decode_with_header::<u32>(*b"CENC", &buffer[..2]),
+        Err(DecodeError::BufferTooSmall)
+    );
+}
+
+#[test]
+fn test_decode_capped() {
+    let buffer = encode_to_vec(&vec![1u32, 2, 3, 4, 5]).unwrap();
+    assert_eq!(
+        decode_capped::<u32>(&mut State::from_buffer(buffer.clone()), 4),
+        Err(DecodeError::ArrayTooLarge)
+    );
+    assert_eq!(
+        decode_capped::<u32>(&mut State::from_buffer(buffer), 5),
+        Ok(vec![1, 2, 3, 4, 5])
+    );
+
+    let buffer = encode_to_vec(&b"bytes".to_vec()).unwrap();
+    assert_eq!(
+        decode_capped::<u8>(&mut State::from_buffer(buffer.clone()), 4),
+        Err(DecodeError::ArrayTooLarge)
+    );
+    assert_eq!(
+        decode_capped::<u8>(&mut State::from_buffer(buffer), 8),
+        Ok(b"bytes".to_vec())
+    );
+}
+
+#[test]
+fn test_encode_seq() {
+    let buffer = encode_seq([1u16, 300, 0xFFFF]).unwrap();
+    // no length prefix
+    assert_eq!(buffer, vec![0xFD, 1, 0, 0xFD, 0x2C, 0x01, 0xFD, 0xFF, 0xFF]);
+    assert_eq!(decode_all::<u16>(&buffer), Ok(vec![1, 300, 0xFFFF]));
+
+    let buffer = encode_seq(["a", "bc"].iter()).unwrap();
+    assert_eq!(
+        decode_all::<String>(&buffer),
+        Ok(vec!["a".to_string(), "bc".to_string()])
+    );
+
+    assert_eq!(encode_seq(Vec::<u32>::new()), Ok(vec![]));
+    assert_eq!(decode_all::<u32>(&[]), Ok(vec![]));
+}
+
+#[test]
+fn test_decode_all_errors() {
+    // truncated last value
+    assert_eq!(
+        decode_all::<u16>(&[0xFD, 1, 0, 0xFD, 1]),
+        Err(DecodeError::BufferTooSmall)
+    );
+    // values without any bytes would never finish
+    assert_eq!(decode_all::<()>(&[1]), Err(DecodeError::TypeMismatch));
+}
+
+#[test]
+fn test_decode_lossy() {
+    let entries = vec![
+        (1u32, String::from("started")),
+        (2u32, String::from("bad")),
+        (3u32, String::from("stopped")),
+    ];
+    let mut buffer = encode_framed(&entries).unwrap();
+    // count, then length + u32 + string for each entry
+    assert_eq!(buffer[..3], [3, 13, 0xFE]);
+    // replace "bad" with invalid utf-8
+    let position = buffer.windows(3).position(|w| w == b"bad").unwrap();
+    buffer[position] = 0xFF;
+
+    let mut state = State::from_buffer(buffer);
+    let (decoded, errors) = decode_lossy::<(u32, String)>(&mut state);
+    assert_eq!(decoded, vec![entries[0].clone(), entries[2].clone()]);
+    assert_eq!(errors, vec![DecodeError::InvalidUtf8]);
+    assert_eq!(state.start, state.end);
+
+    // a truncated region can not be skipped
+    let buffer = encode_framed(&[1u32, 2, 3]).unwrap();
+    let mut state = State::from_buffer(buffer[..buffer.len() - 1].to_vec());
+    let (decoded, errors) = decode_lossy::<u32>(&mut state);
+    assert_eq!(decoded, vec![1, 2]);
+    assert_eq!(
+        errors,
+        vec![DecodeError::InvalidLength {
+            declared: 5,
+            available: 4
+        }]
+    );
+}
+
+#[test]
+fn test_encode_decode() {
+    assert_eq!(decode::<u64>(&encode(&42u64).unwrap()), Ok(42));
+
+    let value = vec![String::from("top"), String::from("level")];
+    let buffer = encode(&value).unwrap();
+    assert_eq!(encode_to_vec(&value), Ok(buffer.clone()));
+    assert_eq!(decode::<Vec<String>>(&buffer), Ok(value));
+
+    assert_eq!(decode::<u64>(&[]), Err(DecodeError::BufferTooSmall));
+}
+
+#[test]
+fn test_sentinel() {
+    const SENTINEL: &[u8] = &[0xFE, 0xED];
+    // the u32 prefix equals the first sentinel byte and is escaped
+    let first = encode_with_sentinel(&0xFEu32, SENTINEL).unwrap();
+    assert_eq!(first, vec![0xFE, 0, 0xFE, 0, 0, 0, 0, 0xFE, 0xED]);
+    let second = encode_with_sentinel(&"second", SENTINEL).unwrap();
+
+    let mut buffer = first.clone();
+    buffer.extend(&second);
+    let mut state = State::from_buffer(buffer);
+    assert_eq!(decode_until_sentinel::<u32>(&mut state, SENTINEL), Ok(0xFE));
+    assert_eq!(
+        decode_until_sentinel::<String>(&mut state, SENTINEL),
+        Ok("second".into())
+    );
+    assert_eq!(state.start, state.end);
+    assert_eq!(
+        decode_until_sentinel::<String>(&mut state, SENTINEL),
+        Err(DecodeError::BufferTooSmall)
+    );
+}
+
+#[test]
+fn test_sentinel_resynchronize() {
+    const SENTINEL: &[u8] = b"\n\r";
+    let first = encode_with_sentinel(&"first", SENTINEL).unwrap();
+    let second = encode_with_sentinel(&vec![1u64, 2], SENTINEL).unwrap();
+
+    // garbage injected into the first message
+    let mut buffer = first[..3].to_vec();
+    buffer.extend([b'\n', 7, 0xFF, 0xFF]);
+    buffer.extend(&first[3..]);
+    buffer.extend(&second);
+
+    let mut state = State::from_buffer(buffer.clone());
+    assert_eq!(
+        decode_until_sentinel::<String>(&mut state, SENTINEL),
+        Err(DecodeError::TypeMismatch)
+    );
+    assert_eq!(
+        decode_until_sentinel::<Vec<u64>>(&mut state, SENTINEL),
+        Ok(vec![1, 2])
+    );
+
+    // garbage between the messages without escape bytes becomes part of the next frame
+    let mut buffer = first.clone();
+    buffer.extend([0xFF, 0x00]);
+    buffer.extend(&second);
+    buffer.extend(&first);
+    let mut state = State::from_buffer(buffer);
+    assert_eq!(
+        decode_until_sentinel::<String>(&mut state, SENTINEL),
+        Ok("first".into())
+    );
+    assert!(decode_until_sentinel::<Vec<u64>>(&mut state, SENTINEL).is_err());
+    assert_eq!(
+        decode_until_sentinel::<String>(&mut state, SENTINEL),
+        Ok("first".into())
+    );
+
+    // a sentinel directly after a corrupt escape byte still ends the frame
+    const OTHER_SENTINEL: &[u8] = &[0xAA, 0x55];
+    let mut buffer = vec![0x01, 0xAA];
+    buffer.extend(OTHER_SENTINEL);
+    buffer.extend(encode_with_sentinel(&"next", OTHER_SENTINEL).unwrap());
+    let mut state = State::from_buffer(buffer);
+    assert_eq!(
+        decode_until_sentinel::<u8>(&mut state, OTHER_SENTINEL),
+        Err(DecodeError::TypeMismatch)
+    );
+    assert_eq!(state.start, 4);
+    assert_eq!(
+        decode_until_sentinel::<String>(&mut state, OTHER_SENTINEL),
+        Ok("next".into())
+    );
+
+    // incomplete message is not consumed
+    let mut state = State::from_buffer(second[..second.len() - 1].to_vec());
+    assert_eq!(
+        decode_until_sentinel::<Vec<u64>>(&mut state, SENTINEL),
+        Err(DecodeError::BufferTooSmall)
+    );
+    assert_eq!(state.start, 0);
+}
@@ -0,0 +1,76 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+
+use crate::interval_tree::*;
+use crate::*;
+
+#[test]
+fn test_interval_tree_roundtrip() {
+    let tree = IntervalTree::new(vec![
+        (1_000_020, 1_000_090),
+        (1_000_000, 1_000_050),
+        (1_000_010, 1_000_011),
+        (1_000_000, 1_000_050),
+        (2_000_000, 1_999_900),
+    ]);
+    assert_eq!(
+        tree.intervals(),
+        [
+            (1_000_000, 1_000_050),
+            (1_000_000, 1_000_050),
+            (1_000_010, 1_000_011),
+            (1_000_020, 1_000_090),
+            (1_999_900, 2_000_000),
+        ]
+    );
+
+    let buffer = encode_to_vec_checked(&tree).unwrap();
+    // the first and the last start require a u32, all other deltas and lengths a single byte
+    assert_eq!(buffer.len(), 1 + (5 + 1) + 3 * 2 + (5 + 1));
+
+    let mut state = State::from_buffer(buffer);
+    let decoded = IntervalTree::decode(&mut state).unwrap();
+    assert_eq!(state.start, state.end);
+    assert_eq!(decoded, tree);
+    assert_eq!(
+        decoded.overlapping(1_000_011, 1_000_015),
+        vec![
+            (1_000_000, 1_000_050),
+            (1_000_000, 1_000_050),
+            (1_000_010, 1_000_011),
+        ]
+    );
+    assert_eq!(
+        decoded.overlapping(1_000_060, 1_999_900),
+        vec![(1_000_020, 1_000_090), (1_999_900, 2_000_000)]
+    );
+    assert!(decoded.overlapping(0, 999_999).is_empty());
+
+    let empty = IntervalTree::default();
+    assert_eq!(encode_to_vec(&empty), Ok(vec![0]));
+    assert_eq!(
+        IntervalTree::decode(&mut State::from_buffer(vec![0])),
+        Ok(empty)
+    );
+}
+
+#[test]
+fn test_interval_tree_invalid() {
+    // end overflows u64
+    let mut state = State::new();
+    state.encode_append(&1usize).unwrap();
+    state.encode_append(&u64::MAX).unwrap();
+    state.encode_append(&1u8).unwrap();
+    state.start = 0;
+    assert_eq!(
+        IntervalTree::decode(&mut state),
+        Err(DecodeError::TypeMismatch)
+    );
+
+    // second interval (5, 6) is sorted before the first one (5, 10)
+    let mut state = State::from_buffer(vec![2, 5, 5, 0, 1]);
+    assert_eq!(
+        IntervalTree::decode(&mut state),
+        Err(DecodeError::TypeMismatch)
+    );
+}
@@ -0,0 +1,95 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+
+use crate::leb128::Leb128;
+use crate::*;
+
+#[test]
+fn test_leb128_u32_vectors() {
+    let vectors: [(u32, Vec<u8>); 6] = [
+        (0, vec![0x00]),
+        (127, vec![0x7F]),
+        (128, vec![0x80, 0x01]),
+        (300, vec![0xAC, 0x02]),
+        (624485, vec![0xE5, 0x8E, 0x26]),
+        (u32::MAX, vec![0xFF, 0xFF, 0xFF, 0xFF, 0x0F]),
+    ];
+    for (value, expected) in vectors {
+        assert_eq!(encoded_size(&Leb128(value)), expected.len());
+        assert_eq!(encode_to_vec(&Leb128(value)), Ok(expected.clone()));
+
+        let mut state = State {
+            start: 0,
+            end: expected.len(),
+            buffer: Some(expected),
+            ..Default::default()
+        };
+        assert_eq!(Leb128::<u32>::decode(&mut state), Ok(Leb128(value)));
+        assert_eq!(state.start, state.end);
+    }
+}
+
+#[test]
+fn test_leb128_u64_vectors() {
+    let vectors: [(u64, Vec<u8>); 3] = [
+        (300, vec![0xAC, 0x02]),
+        (1 << 35, vec![0x80, 0x80, 0x80, 0x80, 0x80, 0x01]),
+        (
+            u64::MAX,
+            vec![0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01],
+        ),
+    ];
+    for (value, expected) in vectors {
+        assert_eq!(encode_to_vec(&Leb128(value)), Ok(expected.clone()));
+
+        let mut state = State {
+            start: 0,
+            end: expected.len(),
+            buffer: Some(expected),
+            ..Default::default()
+        };
+        assert_eq!(Leb128::<u64>::decode(&mut state), Ok(Leb128(value)));
+        assert_eq!(state.start, state.end);
+    }
+}
+
+#[test]
+fn test_leb128_decode_overflow() {
+    // u32::MAX + 1
+    let mut state = State {
+        start: 0,
+        end: 5,
+        buffer: Some(vec![0x80, 0x80, 0x80, 0x80, 0x10]),
+        ..Default::default()
+    };
+    assert_eq!(
+        Leb128::<u32>::decode(&mut state),
+        Err(DecodeError::TypeMismatch)
+    );
+
+    // too many continuation bytes
+    let mut state = State {
+        start: 0,
+        end: 11,
+        buffer: Some(vec![0x80; 11]),
+        ..Default::default()
+    };
+    assert_eq!(
+        Leb128::<u64>::decode(&mut state),
+        Err(DecodeError::TypeMismatch)
+    );
+}
+
+#[test]
+fn test_leb128_decode_truncated() {
+    let mut state = State {
+        start: 0,
+        end: 1,
+        buffer: Some(vec![0xAC]),
+        ..Default::default()
+    };
+    assert_eq!(
+        Leb128::<u32>::decode(&mut state),
+        Err(DecodeError::BufferTooSmall)
+    );
+}
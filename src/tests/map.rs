@@ -0,0 +1,137 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+
+use crate::*;
+use std::collections::{BTreeMap, HashMap};
+
+#[test]
+fn test_btree_map_char_keys() {
+    let mut frequencies: BTreeMap<char, u32> = BTreeMap::new();
+    for c in "hello world 🌾".chars() {
+        *frequencies.entry(c).or_default() += 1;
+    }
+
+    let buffer = encode_to_vec(&frequencies).unwrap();
+    // 9 entries, the first one is ' ' => 2
+    assert_eq!(buffer[..7], [9, b' ', 0xFE, 2, 0, 0, 0]);
+    let mut state = State::from_buffer(buffer);
+    assert_eq!(BTreeMap::<char, u32>::decode(&mut state), Ok(frequencies));
+    assert_eq!(state.start, state.end);
+}
+
+#[test]
+fn test_hash_map_u8_keys() {
+    let map: HashMap<u8, String> = HashMap::from([
+        (3, "three".to_string()),
+        (1, "one".to_string()),
+        (0xFF, "max".to_string()),
+    ]);
+
+    let mut state = State::new();
+    map.pre_encode(&mut state);
+    state.alloc();
+    assert_eq!(map.encode(&mut state), Ok(()));
+    // entries are sorted by key
+    assert!(state.eq_bytes(&[
+        3, 1, 3, b'o', b'n', b'e', 3, 5, b't', b'h', b'r', b'e', b'e', 0xFD, 0xFF, 0x00, 3, b'm',
+        b'a', b'x',
+    ]));
+
+    state.start = 0;
+    assert_eq!(HashMap::<u8, String>::decode(&mut state), Ok(map));
+    assert_eq!(state.start, state.end);
+}
+
+#[test]
+fn test_map_same_layout() {
+    let hash_map: HashMap<u16, bool> = HashMap::from([(500, true), (2, false)]);
+    let btree_map: BTreeMap<u16, bool> = hash_map.clone().into_iter().collect();
+    assert_eq!(encode_to_vec(&hash_map), encode_to_vec(&btree_map));
+}
+
+#[test]
+fn test_map_duplicate_key() {
+    let buffer = vec![2, 1, 1, 1, 0];
+    assert_eq!(
+        BTreeMap::<u8, bool>::decode(&mut State::from_buffer(buffer.clone())),
+        Err(DecodeError::TypeMismatch)
+    );
+    assert_eq!(
+        HashMap::<u8, bool>::decode(&mut State::from_buffer(buffer)),
+        Err(DecodeError::TypeMismatch)
+    );
+}
+
+#[test]
+fn test_map_empty() {
+    assert_eq!(encode_to_vec(&BTreeMap::<char, u32>::new()), Ok(vec![0]));
+    assert_eq!(
+        HashMap::<u8, String>::decode(&mut State::from_buffer(vec![0])),
+        Ok(HashMap::new())
+    );
+}
+
+#[test]
+fn test_map_canonical() {
+    let map: HashMap<String, u8> =
+        HashMap::from([("b".into(), 2), ("a".into(), 1), ("c".into(), 3)]);
+    let mut state = State::from_buffer(encode_to_vec(&map).unwrap());
+    assert_eq!(
+        state.decode_canonical_map::<String, u8, HashMap<_, _>>(),
+        Ok(map)
+    );
+    assert_eq!(state.start, state.end);
+
+    // keys 2, 1 are valid for HashMap, but not sorted
+    let buffer = vec![2, 2, 0, 1, 1];
+    assert_eq!(
+        HashMap::<u8, bool>::decode(&mut State::from_buffer(buffer.clone())).map(|map| map.len()),
+        Ok(2)
+    );
+    assert_eq!(
+        State::from_buffer(buffer).decode_canonical_map::<u8, bool, BTreeMap<_, _>>(),
+        Err(DecodeError::TypeMismatch)
+    );
+
+    // duplicate keys are not strictly increasing
+    let buffer = vec![2, 1, 1, 1, 0];
+    assert_eq!(
+        State::from_buffer(buffer).decode_canonical_map::<u8, bool, BTreeMap<_, _>>(),
+        Err(DecodeError::TypeMismatch)
+    );
+}
+
+#[test]
+fn test_map_deterministic() {
+    let entries: Vec<(String, u32)> = (0..64).map(|i| (format!("key {i}"), i)).collect();
+    let expected = encode_to_vec(&entries.iter().cloned().collect::<BTreeMap<_, _>>()).unwrap();
+
+    // each HashMap uses a different random hasher state and insertion order
+    for reversed in [false, true] {
+        let mut map = HashMap::new();
+        let mut ordered = entries.clone();
+        if reversed {
+            ordered.reverse();
+        }
+        map.extend(ordered);
+        assert_eq!(encode_to_vec(&map), Ok(expected.clone()));
+    }
+    let btree_map: BTreeMap<String, u32> = entries.into_iter().rev().collect();
+    assert_eq!(encode_to_vec(&btree_map), Ok(expected));
+}
+
+#[test]
+fn test_map_count_exceeds_max() {
+    let count = MAX_ARRAY_DECODE_SIZE + 1;
+    let mut buffer = encode_to_vec(&count).unwrap();
+    buffer.resize(buffer.len() + 2 * count, 0);
+
+    assert_eq!(
+        HashMap::<u8, u8>::decode(&mut State::from_buffer(buffer.clone())),
+        Err(DecodeError::ArrayTooLarge)
+    );
+    assert_eq!(
+        BTreeMap::<u8, u8>::decode(&mut State::from_buffer(buffer)),
+        Err(DecodeError::ArrayTooLarge)
+    );
+}
@@ -0,0 +1,64 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+
+use crate::merkle::*;
+use crate::*;
+
+/// simple non-cryptographic hash for testing, byte-wise rotate and xor
+fn hash(left: &Fixed32, right: &Fixed32) -> Fixed32 {
+    let mut out = [0u8; 32];
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = left.as_bytes()[i].rotate_left(3) ^ right.as_bytes()[31 - i] ^ i as u8;
+    }
+    Fixed::from(out)
+}
+
+#[test]
+fn test_merkle_proof_roundtrip() {
+    // tree with 8 leaves
+    let leaves: Vec<Fixed32> = (0..8u8).map(|i| Fixed::from([i; 32])).collect();
+    let level1: Vec<Fixed32> = leaves.chunks(2).map(|c| hash(&c[0], &c[1])).collect();
+    let level2: Vec<Fixed32> = level1.chunks(2).map(|c| hash(&c[0], &c[1])).collect();
+    let root = hash(&level2[0], &level2[1]);
+
+    // proof for leaf 5 = 0b101
+    let proof = MerkleProof {
+        leaf_index: 5,
+        leaf: leaves[5].clone(),
+        siblings: vec![leaves[4].clone(), level1[3].clone(), level2[0].clone()],
+    };
+    assert_eq!(proof.root(hash), root);
+
+    let buffer = encode_to_vec_checked(&proof).unwrap();
+    assert_eq!(buffer.len(), 1 + 32 + 1 + 3 * 32);
+    assert_eq!(buffer[..2], [5, 5]);
+    assert_eq!(buffer[34..66], [4; 32]);
+
+    let mut state = State::from_buffer(buffer);
+    let decoded = MerkleProof::decode(&mut state).unwrap();
+    assert_eq!(state.start, state.end);
+    assert_eq!(decoded.leaf_index, 5);
+    assert_eq!(decoded.leaf.as_bytes(), leaves[5].as_bytes());
+    for (decoded, expected) in decoded.siblings.iter().zip(&proof.siblings) {
+        assert_eq!(decoded.as_bytes(), expected.as_bytes());
+    }
+    assert_eq!(decoded, proof);
+    assert_eq!(decoded.root(hash), root);
+}
+
+#[test]
+fn test_merkle_proof_truncated() {
+    let proof = MerkleProof {
+        leaf_index: u64::MAX,
+        leaf: Fixed::from([1; 32]),
+        siblings: vec![Fixed::from([2; 32]); 2],
+    };
+    let buffer = encode_to_vec(&proof).unwrap();
+    assert_eq!(buffer[0], U64_PREFIX);
+
+    let mut state = State::from_buffer(buffer[..buffer.len() - 1].to_vec());
+    assert_eq!(
+        MerkleProof::decode(&mut state),
+        Err(DecodeError::BufferTooSmall)
+    );
+}
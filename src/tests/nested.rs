@@ -0,0 +1,62 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+
+use super::util::round_trip;
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+use std::sync::Arc;
+
+#[test]
+fn test_nested_vec_vec_string() {
+    let value = vec![
+        vec![String::from("a"), String::from("bc")],
+        vec![],
+        vec![String::from("def")],
+    ];
+    assert_eq!(round_trip(&value), Ok(value));
+}
+
+#[test]
+fn test_nested_vec_of_arrays() {
+    let value = vec![[1u16, 2, 3], [4, 5, 6000]];
+    assert_eq!(round_trip(&value), Ok(value));
+}
+
+#[test]
+fn test_nested_array_of_vecs() {
+    let value = [vec![1u32, 2, 3], vec![u32::MAX]];
+    assert_eq!(round_trip(&value), Ok(value));
+}
+
+#[test]
+fn test_nested_vec_vec_u8() {
+    let value = vec![vec![0u8, 0xFF], vec![], vec![42u8; 300]];
+    assert_eq!(round_trip(&value), Ok(value));
+}
+
+#[test]
+fn test_nested_shared_pointers() {
+    let value = Rc::new(vec![
+        Arc::new(String::from("x")),
+        Arc::new(String::from("yz")),
+    ]);
+    assert_eq!(round_trip(&value), Ok(value));
+}
+
+#[test]
+fn test_nested_vec_vec_char_arrays() {
+    let value = vec![vec![['a', 'b'], ['🌾', 'c']], vec![['d', 'e']]];
+    assert_eq!(round_trip(&value), Ok(value));
+}
+
+#[test]
+fn test_nested_maps() {
+    let value: Vec<HashMap<String, BTreeMap<u8, Vec<u32>>>> = vec![
+        HashMap::from([
+            ("a".into(), BTreeMap::from([(1, vec![1, 2]), (2, vec![])])),
+            ("b".into(), BTreeMap::new()),
+        ]),
+        HashMap::new(),
+    ];
+    assert_eq!(round_trip(&value), Ok(value));
+}
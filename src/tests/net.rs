@@ -0,0 +1,165 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+
+use crate::net::*;
+use crate::*;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+#[test]
+fn test_ip_addr() {
+    let v4 = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+    assert_eq!(encode_to_vec(&v4), Ok(vec![4, 127, 0, 0, 1]));
+    let mut state = State::from_buffer(encode_to_vec(&v4).unwrap());
+    assert_eq!(IpAddr::decode(&mut state), Ok(v4));
+
+    let v6 = IpAddr::V6(Ipv6Addr::LOCALHOST);
+    assert_eq!(encoded_size(&v6), 17);
+    let mut state = State::from_buffer(encode_to_vec(&v6).unwrap());
+    assert_eq!(IpAddr::decode(&mut state), Ok(v6));
+    assert_eq!(state.start, state.end);
+}
+
+#[test]
+fn test_socket_addr_v4() {
+    let address = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 2), 8080));
+    assert_eq!(
+        encode_to_vec(&address),
+        Ok(vec![4, 10, 0, 0, 2, 0x90, 0x1F])
+    );
+
+    let mut state = State::from_buffer(encode_to_vec(&address).unwrap());
+    assert_eq!(SocketAddr::decode(&mut state), Ok(address));
+    assert_eq!(state.start, state.end);
+}
+
+#[test]
+fn test_socket_addr_v6() {
+    let address = SocketAddr::V6(SocketAddrV6::new(
+        "fe80::1".parse().unwrap(),
+        443,
+        0x12345,
+        3,
+    ));
+    assert_eq!(encoded_size(&address), 1 + 16 + 2 + 4 + 4);
+
+    let mut state = State::from_buffer(encode_to_vec(&address).unwrap());
+    let decoded = SocketAddr::decode(&mut state).unwrap();
+    assert_eq!(decoded, address);
+    match decoded {
+        SocketAddr::V6(decoded) => {
+            assert_eq!(decoded.flowinfo(), 0x12345);
+            assert_eq!(decoded.scope_id(), 3);
+        }
+        SocketAddr::V4(_) => panic!("decoded wrong address family"),
+    }
+    assert_eq!(state.start, state.end);
+}
+
+#[test]
+fn test_socket_addr_unknown_family() {
+    let mut buffer = encode_to_vec(&SocketAddr::from(([1, 2, 3, 4], 80))).unwrap();
+    buffer[0] = 5;
+    let mut state = State::from_buffer(buffer.clone());
+    assert_eq!(
+        SocketAddr::decode(&mut state),
+        Err(DecodeError::TypeMismatch)
+    );
+    let mut state = State::from_buffer(buffer);
+    assert_eq!(IpAddr::decode(&mut state), Err(DecodeError::TypeMismatch));
+}
+
+#[test]
+fn test_domain_name() {
+    let name = DomainName::new("example.com").unwrap();
+    let buffer = encode_to_vec(&name).unwrap();
+    assert_eq!(buffer, b"\x07example\x03com\x00");
+    let mut state = State::from_buffer(buffer);
+    assert_eq!(DomainName::decode(&mut state), Ok(name.clone()));
+    assert_eq!(state.start, state.end);
+    assert_eq!(DomainName::new("example.com."), Some(name));
+
+    let root = DomainName::new("").unwrap();
+    assert_eq!(encode_to_vec(&root), Ok(vec![0]));
+    assert_eq!(
+        DomainName::decode(&mut State::from_buffer(vec![0])),
+        Ok(root)
+    );
+}
+
+#[test]
+fn test_domain_name_invalid() {
+    let label = "a".repeat(MAX_LABEL_LENGTH);
+    assert!(DomainName::new(&format!("{label}.com")).is_some());
+    assert_eq!(DomainName::new(&format!("{label}a.com")), None);
+    assert_eq!(DomainName::new("example..com"), None);
+    // 4 labels of 63 bytes exceed the total length
+    assert_eq!(DomainName::new(&[label.as_str(); 4].join(".")), None);
+
+    let mut buffer = vec![MAX_LABEL_LENGTH as u8 + 1];
+    buffer.extend([b'a'; MAX_LABEL_LENGTH + 1]);
+    buffer.push(0);
+    let mut state = State::from_buffer(buffer);
+    assert_eq!(
+        DomainName::decode(&mut state),
+        Err(DecodeError::TypeMismatch)
+    );
+
+    let mut buffer = Vec::new();
+    for _ in 0..4 {
+        buffer.push(MAX_LABEL_LENGTH as u8);
+        buffer.extend(label.as_bytes());
+    }
+    buffer.push(0);
+    let mut state = State::from_buffer(buffer);
+    assert_eq!(
+        DomainName::decode(&mut state),
+        Err(DecodeError::StringTooLarge)
+    );
+
+    // compression pointers must point before the name
+    let mut state = State::from_buffer(vec![0xC0, 0x00]);
+    assert_eq!(
+        DomainName::decode(&mut state),
+        Err(DecodeError::TypeMismatch)
+    );
+    let mut state = State::from_buffer(vec![0xC0, 0x02, 0]);
+    assert_eq!(
+        DomainName::decode(&mut state),
+        Err(DecodeError::TypeMismatch)
+    );
+
+    // a pointer to a label followed by a pointer to itself
+    let mut state = State::from_buffer(vec![1, b'a', 0xC0, 0x00, 0xC0, 0x00]);
+    state.start = 4;
+    assert_eq!(
+        DomainName::decode(&mut state),
+        Err(DecodeError::TypeMismatch)
+    );
+}
+
+#[test]
+fn test_domain_name_compression() {
+    let mut buffer = encode_to_vec(&DomainName::new("example.com").unwrap()).unwrap();
+    // "www" followed by a pointer to "example.com" at offset 0
+    buffer.extend(b"\x03www\xC0\x00");
+    // "mail" followed by a pointer to "www" at offset 13
+    buffer.extend(b"\x04mail\xC0\x0D");
+    buffer.push(42);
+
+    let mut state = State::from_buffer(buffer);
+    assert_eq!(
+        DomainName::decode(&mut state).unwrap().as_str(),
+        "example.com"
+    );
+    assert_eq!(
+        DomainName::decode(&mut state).unwrap().as_str(),
+        "www.example.com"
+    );
+    assert_eq!(
+        DomainName::decode(&mut state).unwrap().as_str(),
+        "mail.www.example.com"
+    );
+    // decoding continues after the first pointer
+    assert_eq!(u8::decode(&mut state), Ok(42));
+    assert_eq!(state.start, state.end);
+}
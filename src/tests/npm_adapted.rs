@@ -19,6 +19,7 @@ mod tests {
                 start: 0,
                 end: 1,
                 buffer: None,
+                ..Default::default()
             }
         );
 
@@ -29,6 +30,7 @@ mod tests {
                 start: 0,
                 end: 4,
                 buffer: None,
+                ..Default::default()
             }
         );
 
@@ -39,6 +41,7 @@ mod tests {
                 start: 0,
                 end: 13,
                 buffer: None,
+                ..Default::default()
             }
         );
 
@@ -51,6 +54,7 @@ mod tests {
                 start: 1,
                 end: 13,
                 buffer: Some(vec![42, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]),
+                ..Default::default()
             }
         );
 
@@ -61,6 +65,7 @@ mod tests {
                 start: 4,
                 end: 13,
                 buffer: Some(vec![42, 0xFD, 104, 16, 0, 0, 0, 0, 0, 0, 0, 0, 0]),
+                ..Default::default()
             }
         );
         assert_eq!(MAX_SAFE_INTEGER.encode(&mut state), Ok(()));
@@ -72,6 +77,7 @@ mod tests {
                 buffer: Some(vec![
                     42, 0xFD, 104, 16, 0xFF, 255, 255, 255, 255, 255, 255, 31, 0
                 ]),
+                ..Default::default()
             }
         );
 
@@ -104,6 +110,7 @@ mod tests {
                 start: 0,
                 end: 1,
                 buffer: None,
+                ..Default::default()
             }
         );
 
@@ -114,6 +121,7 @@ mod tests {
                 start: 0,
                 end: 4,
                 buffer: None,
+                ..Default::default()
             }
         );
 
@@ -126,6 +134,7 @@ mod tests {
                 start: 1,
                 end: 4,
                 buffer: Some(vec![84, 0, 0, 0]),
+                ..Default::default()
             }
         );
 
@@ -136,6 +145,7 @@ mod tests {
                 start: 4,
                 end: 4,
                 buffer: Some(vec![84, 0xFD, 207, 32]),
+                ..Default::default()
             }
         );
 
@@ -165,6 +175,7 @@ mod tests {
                 start: 0,
                 end: 8,
                 buffer: None,
+                ..Default::default()
             }
         );
 
@@ -175,6 +186,7 @@ mod tests {
                 start: 0,
                 end: 8,
                 buffer: Some(vec![0, 0, 0, 0, 0, 0, 0, 0]),
+                ..Default::default()
             }
         );
 
@@ -185,6 +197,7 @@ mod tests {
                 start: 8,
                 end: 8,
                 buffer: Some(vec![0x87, 0xC9, 0xAF, 0x7A, 0x9B, 0x47, 0x64, 0x40]),
+                ..Default::default()
             }
         );
 
@@ -205,6 +218,7 @@ mod tests {
                 start: 0,
                 end: 9,
                 buffer: None,
+                ..Default::default()
             }
         );
 
@@ -215,6 +229,7 @@ mod tests {
                 start: 0,
                 end: 9,
                 buffer: Some(vec![0, 0, 0, 0, 0, 0, 0, 0, 0]),
+                ..Default::default()
             }
         );
 
@@ -226,6 +241,7 @@ mod tests {
                 start: 9,
                 end: 9,
                 buffer: Some(vec![0, 0x87, 0xC9, 0xAF, 0x7A, 0x9B, 0x47, 0x64, 0x40]),
+                ..Default::default()
             }
         );
 
@@ -278,6 +294,7 @@ mod tests {
                 start: 8,
                 end: 8,
                 buffer: Some(vec![0, 0, 0, 0, 0, 0, 0, 0]),
+                ..Default::default()
             }
         );
 
@@ -297,6 +314,7 @@ mod tests {
                 start: 8,
                 end: 8,
                 buffer: Some(vec![0, 0, 0, 0, 0, 0, 0xF0, 0x7F]),
+                ..Default::default()
             }
         );
 
@@ -316,6 +334,7 @@ mod tests {
                 start: 8,
                 end: 8,
                 buffer: Some(vec![0x34, 0x33, 0x33, 0x33, 0x33, 0x33, 0xD3, 0x3F]),
+                ..Default::default()
             }
         );
 
@@ -335,6 +354,7 @@ mod tests {
                 start: 0,
                 end: 3,
                 buffer: None,
+                ..Default::default()
             }
         );
         Some("hello".as_bytes()).pre_encode(&mut state);
@@ -344,6 +364,7 @@ mod tests {
                 start: 0,
                 end: 9,
                 buffer: None,
+                ..Default::default()
             }
         );
         None.pre_encode(&mut state);
@@ -353,6 +374,7 @@ mod tests {
                 start: 0,
                 end: 10,
                 buffer: None,
+                ..Default::default()
             }
         );
 
@@ -369,6 +391,7 @@ mod tests {
                     0, 0, 0, 0, 0, 0, // "hello"
                     0, // None
                 ]),
+                ..Default::default()
             }
         );
         assert_eq!(Some("hello".as_bytes()).encode(&mut state), Ok(()));
@@ -382,6 +405,7 @@ mod tests {
                     5, 'h' as u8, 'e' as u8, 'l' as u8, 'l' as u8, 'o' as u8, // "hello"
                     0,         // None
                 ]),
+                ..Default::default()
             }
         );
         assert_eq!(None.encode(&mut state), Ok(()));
@@ -395,6 +419,7 @@ mod tests {
                     5, 'h' as u8, 'e' as u8, 'l' as u8, 'l' as u8, 'o' as u8, // "hello"
                     0,         // None
                 ]),
+                ..Default::default()
             }
         );
 
@@ -439,6 +464,7 @@ mod tests {
                 start: 0,
                 end: 5,
                 buffer: None,
+                ..Default::default()
             }
         );
 
@@ -449,6 +475,7 @@ mod tests {
                 start: 0,
                 end: 14,
                 buffer: None,
+                ..Default::default()
             }
         );
 
@@ -464,6 +491,7 @@ mod tests {
                     1, 1, 0, 0, 0, // [1]
                     0, 0, 0, 0, 0, 0, 0, 0, 0 // [42, 43]
                 ]),
+                ..Default::default()
             }
         );
 
@@ -480,6 +508,7 @@ mod tests {
                     1, 1, 0, 0, 0, // [1]
                     2, 42, 0, 0, 0, 43, 0, 0, 0 // [42, 43]
                 ]),
+                ..Default::default()
             }
         );
 
@@ -511,6 +540,7 @@ mod tests {
                 start: 0,
                 end: 4,
                 buffer: None,
+                ..Default::default()
             }
         );
 
@@ -521,6 +551,7 @@ mod tests {
                 start: 0,
                 end: 9,
                 buffer: None,
+                ..Default::default()
             }
         );
 
@@ -537,6 +568,7 @@ mod tests {
                     0, // vec length
                     0, 0, 0, 0, // vec content
                 ]),
+                ..Default::default()
             }
         );
 
@@ -552,6 +584,7 @@ mod tests {
                     4, // vec length
                     0, 0, 1, 1, // vec content
                 ]),
+                ..Default::default()
             }
         );
 
@@ -581,6 +614,7 @@ mod tests {
                 start: 0,
                 end: 5,
                 buffer: None,
+                ..Default::default()
             }
         );
 
@@ -591,6 +625,7 @@ mod tests {
                 start: 0,
                 end: 20,
                 buffer: None,
+                ..Default::default()
             }
         );
 
@@ -607,6 +642,7 @@ mod tests {
                         .as_bytes()
                         .to_vec()
                 ),
+                ..Default::default()
             }
         );
 
@@ -617,6 +653,7 @@ mod tests {
                 start: 20,
                 end: 20,
                 buffer: Some("\x04🌾\x0ehøsten er fin".as_bytes().to_vec()),
+                ..Default::default()
             }
         );
 
@@ -641,6 +678,7 @@ mod tests {
                 start: 0,
                 end: 2,
                 buffer: None,
+                ..Default::default()
             }
         );
 
@@ -654,6 +692,7 @@ mod tests {
                 buffer: Some(vec![
                     'h' as u8, 'i' as u8, // "hi"
                 ]),
+                ..Default::default()
             }
         );
 
@@ -676,6 +715,7 @@ mod tests {
                 start: 0,
                 end: 32,
                 buffer: None,
+                ..Default::default()
             }
         );
 
@@ -686,6 +726,7 @@ mod tests {
                 start: 0,
                 end: 64,
                 buffer: None,
+                ..Default::default()
             }
         );
 
@@ -699,6 +740,7 @@ mod tests {
                 start: 32,
                 end: 64,
                 buffer: Some(test_buffer_a.to_vec()),
+                ..Default::default()
             }
         );
 
@@ -712,6 +754,7 @@ mod tests {
                 start: 64,
                 end: 64,
                 buffer: Some(test_buffer_b.to_vec()),
+                ..Default::default()
             }
         );
 
@@ -739,6 +782,7 @@ mod tests {
                 start: 0,
                 end: 64,
                 buffer: None,
+                ..Default::default()
             }
         );
 
@@ -749,6 +793,7 @@ mod tests {
                 start: 0,
                 end: 128,
                 buffer: None,
+                ..Default::default()
             }
         );
 
@@ -762,6 +807,7 @@ mod tests {
                 start: 64,
                 end: 128,
                 buffer: Some(test_buffer_a.to_vec()),
+                ..Default::default()
             }
         );
 
@@ -775,6 +821,7 @@ mod tests {
                 start: 128,
                 end: 128,
                 buffer: Some(test_buffer_b.to_vec()),
+                ..Default::default()
             }
         );
 
@@ -802,6 +849,7 @@ mod tests {
                 start: 0,
                 end: 3,
                 buffer: None,
+                ..Default::default()
             }
         );
 
@@ -812,6 +860,7 @@ mod tests {
                 start: 0,
                 end: 6,
                 buffer: None,
+                ..Default::default()
             }
         );
 
@@ -825,6 +874,7 @@ mod tests {
                 start: 3,
                 end: 6,
                 buffer: Some(test_buffer_a.to_vec()),
+                ..Default::default()
             }
         );
 
@@ -838,6 +888,7 @@ mod tests {
                 start: 6,
                 end: 6,
                 buffer: Some(test_buffer_b.to_vec()),
+                ..Default::default()
             }
         );
 
@@ -856,4 +907,37 @@ mod tests {
             Err(DecodeError::BufferTooSmall)
         );
     }
+
+    #[test]
+    fn test_js_array() {
+        // reference output of c.encode(c.array(c.uint), value)
+        let cases: Vec<(Vec<usize>, Vec<u8>)> = vec![
+            (vec![], vec![0x00]),
+            (vec![1, 2, 3], vec![0x03, 0x01, 0x02, 0x03]),
+            (vec![0xfd], vec![0x01, 0xfd, 0xfd, 0x00]),
+            (vec![0x10000], vec![0x01, 0xfe, 0x00, 0x00, 0x01, 0x00]),
+        ];
+        for (value, expected) in cases {
+            let value = JsArray(value);
+            assert_eq!(encode_to_vec(&value), Ok(expected.clone()));
+            let mut state = State::from_buffer(expected);
+            assert_eq!(JsArray::<usize>::decode(&mut state), Ok(value));
+            assert_eq!(state.start, state.end);
+        }
+
+        // reference output of c.encode(c.array(c.string), ['a', 'bc'])
+        let value = JsArray(vec![String::from("a"), String::from("bc")]);
+        let expected = vec![0x02, 0x01, 0x61, 0x02, 0x62, 0x63];
+        assert_eq!(encode_to_vec(&value), Ok(expected.clone()));
+        let mut state = State::from_buffer(expected);
+        assert_eq!(JsArray::<String>::decode(&mut state), Ok(value));
+
+        // reference output of c.encode(c.array(c.uint), new Array(300).fill(0))
+        let value = JsArray(vec![0usize; 300]);
+        let mut expected = vec![0xfd, 0x2c, 0x01];
+        expected.extend([0x00; 300]);
+        assert_eq!(encode_to_vec(&value), Ok(expected.clone()));
+        let mut state = State::from_buffer(expected);
+        assert_eq!(JsArray::<usize>::decode(&mut state), Ok(value));
+    }
 }
@@ -0,0 +1,126 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+
+//! property tests asserting that decode(encode(x)) == x
+
+use super::util::round_trip;
+use crate::*;
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn prop_bool(value: bool) {
+        prop_assert_eq!(round_trip(&value), Ok(value));
+    }
+
+    #[test]
+    fn prop_u8(value: u8) {
+        prop_assert_eq!(round_trip(&value), Ok(value));
+    }
+
+    #[test]
+    fn prop_u16(value: u16) {
+        prop_assert_eq!(round_trip(&value), Ok(value));
+    }
+
+    #[test]
+    fn prop_u32(value: u32) {
+        prop_assert_eq!(round_trip(&value), Ok(value));
+    }
+
+    #[test]
+    fn prop_u64(value: u64) {
+        prop_assert_eq!(round_trip(&value), Ok(value));
+    }
+
+    #[test]
+    fn prop_usize(value: usize) {
+        prop_assert_eq!(round_trip(&value), Ok(value));
+    }
+
+    #[test]
+    fn prop_u128(value: u128) {
+        prop_assert_eq!(round_trip(&value), Ok(value));
+    }
+
+    #[test]
+    fn prop_i8(value: i8) {
+        prop_assert_eq!(round_trip(&value), Ok(value));
+    }
+
+    #[test]
+    fn prop_i16(value: i16) {
+        prop_assert_eq!(round_trip(&value), Ok(value));
+    }
+
+    #[test]
+    fn prop_i32(value: i32) {
+        prop_assert_eq!(round_trip(&value), Ok(value));
+    }
+
+    #[test]
+    fn prop_i64(value: i64) {
+        prop_assert_eq!(round_trip(&value), Ok(value));
+    }
+
+    #[test]
+    fn prop_i128(value: i128) {
+        prop_assert_eq!(round_trip(&value), Ok(value));
+    }
+
+    #[test]
+    fn prop_isize(value: isize) {
+        prop_assert_eq!(round_trip(&value), Ok(value));
+    }
+
+    #[test]
+    fn prop_f32(value in proptest::num::f32::ANY) {
+        let decoded = round_trip(&value).expect("could not decode");
+        prop_assert_eq!(decoded.to_bits(), value.to_bits());
+    }
+
+    #[test]
+    fn prop_f64(value in proptest::num::f64::ANY) {
+        let decoded = round_trip(&value).expect("could not decode");
+        prop_assert_eq!(decoded.to_bits(), value.to_bits());
+    }
+
+    #[test]
+    fn prop_char(value: char) {
+        prop_assert_eq!(round_trip(&value), Ok(value));
+    }
+
+    #[test]
+    fn prop_string(value: String) {
+        prop_assert_eq!(round_trip(&value), Ok(value));
+    }
+
+    #[test]
+    fn prop_vec_u32(value: Vec<u32>) {
+        prop_assert_eq!(round_trip(&value), Ok(value));
+    }
+
+    #[test]
+    fn prop_vec_u8(value: Vec<u8>) {
+        prop_assert_eq!(round_trip(&value), Ok(value));
+    }
+
+    #[test]
+    fn prop_vec_string(value: Vec<String>) {
+        prop_assert_eq!(round_trip(&value), Ok(value));
+    }
+
+    /// decoding arbitrary bytes must fail gracefully instead of panicking
+    /// see fuzz/fuzz_targets/decode.rs for the corresponding fuzz target
+    #[test]
+    fn prop_decode_arbitrary_bytes(bytes: Vec<u8>) {
+        let _ = decode_lenient::<u64>(&bytes);
+        let _ = decode_lenient::<char>(&bytes);
+        let _ = decode_lenient::<String>(&bytes);
+        let _ = decode_lenient::<Vec<u32>>(&bytes);
+        let _ = decode_lenient::<Vec<String>>(&bytes);
+        let _ = decode_lenient::<std::collections::HashMap<u32, String>>(&bytes);
+        let _ = decode_lenient::<std::collections::HashMap<String, u32>>(&bytes);
+        let _ = decode_lenient::<PackedBools>(&bytes);
+    }
+}
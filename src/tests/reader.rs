@@ -0,0 +1,107 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+
+use crate::*;
+
+#[test]
+fn test_chained_reader_decode() {
+    let value: u64 = 0x1234_5678_9ABC_DEF0;
+    let mut buffer = encode_to_vec(&7u8).unwrap();
+    buffer.extend(encode_to_vec(&value).unwrap());
+    buffer.extend(encode_to_vec(&"hello").unwrap());
+
+    // u64 straddles the first two chunks, the string the last two
+    let mut reader = ChainedReader::new(vec![&buffer[..4], &buffer[4..13]]);
+    reader.push(&buffer[13..]);
+    assert_eq!(reader.remaining(), buffer.len());
+    assert_eq!(reader.decode::<u8>(), Ok(7));
+    assert_eq!(reader.decode::<u64>(), Ok(value));
+    assert_eq!(reader.decode::<String>(), Ok("hello".into()));
+    assert_eq!(reader.remaining(), 0);
+    assert_eq!(reader.decode::<u8>(), Err(DecodeError::BufferTooSmall));
+}
+
+#[test]
+fn test_chained_reader_truncated() {
+    let buffer = encode_to_vec(&u64::MAX).unwrap();
+    let mut reader = ChainedReader::new(vec![&buffer[..3], &buffer[3..6]]);
+    assert_eq!(reader.decode::<u64>(), Err(DecodeError::BufferTooSmall));
+    // nothing is consumed on errors
+    assert_eq!(reader.remaining(), 6);
+}
+
+#[test]
+fn test_chained_reader_single_byte_chunks() {
+    // every chunk is appended once while the string is incomplete
+    let buffer = encode_seq(["spanning", "chunks"]).unwrap();
+    let mut reader = ChainedReader::new(buffer.chunks(1).collect());
+    assert_eq!(reader.decode::<String>(), Ok("spanning".into()));
+    assert_eq!(reader.decode::<String>(), Ok("chunks".into()));
+    assert_eq!(reader.remaining(), 0);
+    assert_eq!(reader.decode::<()>(), Ok(()));
+}
+
+#[test]
+fn test_chained_reader_many_values() {
+    // values are decoded in place, so a large chunk is not copied for every value
+    let count = 1_000_000;
+    let values: Vec<u32> = (0..count).collect();
+    let buffer = encode_seq(&values).unwrap();
+    // one value in the middle straddles the chunks
+    let split = buffer.len() / 2 + 2;
+    let mut reader = ChainedReader::new(vec![&buffer[..split], &buffer[split..]]);
+    for value in values {
+        assert_eq!(reader.decode::<u32>(), Ok(value));
+    }
+    assert_eq!(reader.remaining(), 0);
+}
+
+#[test]
+fn test_chained_reader_read_next() {
+    let first = [1u8, 2, 3];
+    let second = [];
+    let third = [4u8, 5];
+    let mut reader = ChainedReader::new(vec![&first, &second, &third]);
+
+    assert_eq!(reader.peek_u8(), Ok(1));
+    assert_eq!(reader.read_next(2), Ok(&[1u8, 2][..]));
+    assert_eq!(reader.read_next(2), Ok(&[3u8, 4][..]));
+    assert_eq!(reader.read_next(2), Err(DecodeError::BufferTooSmall));
+    assert_eq!(reader.peek_u8(), Ok(5));
+    assert_eq!(reader.read_remaining(), Ok(&[5u8][..]));
+    assert_eq!(reader.read_next(0), Ok(&[][..]));
+    assert_eq!(reader.peek_u8(), Err(DecodeError::BufferTooSmall));
+}
+
+#[test]
+fn test_hashing_reader() {
+    use std::collections::hash_map::DefaultHasher;
+
+    // message followed by the checksum of its encoding
+    let mut state = State::new();
+    state.encode_append(&42u32).unwrap();
+    state.encode_append(&"payload").unwrap();
+    let mut writer = HashingWriter::new(DefaultHasher::new());
+    writer.write(&state.buffer.clone().unwrap()).unwrap();
+    let checksum = writer.finish();
+    state.encode_append(&checksum).unwrap();
+
+    state.start = 0;
+    let mut reader = HashingReader::new(&mut state, DefaultHasher::new());
+    assert_eq!(reader.decode::<u32>(), Ok(42));
+    assert_eq!(reader.read_next(1), Ok(&[7u8][..]));
+    assert_eq!(reader.read_next(7), Ok(&b"payload"[..]));
+    let running = reader.finish();
+    assert_eq!(running, checksum);
+    assert_eq!(u64::decode(&mut state), Ok(running));
+    assert_eq!(state.start, state.end);
+
+    // corrupted payload
+    let mut corrupted = state.buffer.clone().unwrap();
+    corrupted[6] = b'P';
+    let mut state = State::from_buffer(corrupted);
+    let mut reader = HashingReader::new(&mut state, DefaultHasher::new());
+    assert_eq!(reader.decode::<u32>(), Ok(42));
+    assert_eq!(reader.decode::<String>(), Ok("Payload".into()));
+    assert_ne!(reader.finish(), checksum);
+}
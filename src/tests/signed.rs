@@ -0,0 +1,70 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+
+use crate::*;
+
+fn roundtrip_i64(value: i64) {
+    let mut state = State::new();
+    value.pre_encode(&mut state);
+    state.alloc();
+    assert_eq!(value.encode(&mut state), Ok(()));
+    assert_eq!(state.start, state.end);
+
+    state.start = 0;
+    assert_eq!(i64::decode(&mut state), Ok(value));
+    assert_eq!(state.start, state.end);
+}
+
+#[test]
+fn test_int64_roundtrip() {
+    for value in [
+        i64::MIN,
+        i64::MAX,
+        -4200,
+        -5_000_000_000,
+        5_000_000_000,
+        -1,
+        0,
+    ] {
+        roundtrip_i64(value);
+    }
+}
+
+#[test]
+fn test_int64_encode_wide() {
+    // values above the u32 range need the full u64 width on the wire
+    let buffer = encode_to_vec(&-5_000_000_000i64).unwrap();
+    assert_eq!(buffer.len(), 9);
+    assert_eq!(buffer[0], U64_PREFIX);
+    assert_eq!(
+        u64::decode(&mut State::from_buffer(buffer)),
+        Ok(zig_zag_encode(-5_000_000_000))
+    );
+
+    assert_eq!(
+        encode_to_vec(&i64::MIN),
+        Ok(vec![
+            U64_PREFIX, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF
+        ])
+    );
+}
+
+#[test]
+fn test_int128() {
+    for value in [i128::MIN, i128::MAX, i64::MIN as i128 - 1, -1, 0, 1] {
+        let buffer = encode_to_vec(&value).unwrap();
+        assert_eq!(buffer.len(), 17);
+        assert_eq!(buffer[0], U128_PREFIX);
+        assert_eq!(i128::decode(&mut State::from_buffer(buffer)), Ok(value));
+    }
+
+    // zig-zag encoded like the smaller signed integers
+    assert_eq!(
+        u128::decode(&mut State::from_buffer(encode_to_vec(&-2i128).unwrap())),
+        Ok(3)
+    );
+    assert_eq!(
+        encode_to_vec(&i128::MIN),
+        Ok([&[U128_PREFIX][..], &[0xFF; 16]].concat())
+    );
+}
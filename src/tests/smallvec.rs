@@ -0,0 +1,44 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+
+use crate::*;
+use smallvec::{smallvec, SmallVec};
+
+#[test]
+fn test_smallvec_inline() {
+    let vec: SmallVec<[u32; 4]> = smallvec![1, 0xFFFF_FFFF];
+    let buffer = encode_to_vec(&vec).unwrap();
+    assert_eq!(Ok(&buffer), encode_to_vec(&vec.to_vec()).as_ref());
+
+    let mut state = State::from_buffer(buffer);
+    let decoded = SmallVec::<[u32; 4]>::decode(&mut state).unwrap();
+    assert!(!decoded.spilled());
+    assert_eq!(decoded, vec);
+    assert_eq!(state.start, state.end);
+}
+
+#[test]
+fn test_smallvec_spilled() {
+    let vec: SmallVec<[u32; 4]> = (0..10).collect();
+    assert!(vec.spilled());
+    let buffer = encode_to_vec(&vec).unwrap();
+    assert_eq!(Ok(&buffer), encode_to_vec(&vec.to_vec()).as_ref());
+
+    let mut state = State::from_buffer(buffer);
+    let decoded = SmallVec::<[u32; 4]>::decode(&mut state).unwrap();
+    assert!(decoded.spilled());
+    assert_eq!(decoded, vec);
+    assert_eq!(state.start, state.end);
+}
+
+#[test]
+fn test_smallvec_bytes() {
+    let vec: SmallVec<[u8; 8]> = smallvec![0xFD, 0xFF, 1];
+    let buffer = encode_to_vec(&vec).unwrap();
+    // bytes are encoded as buffer, same as Vec<u8>
+    assert_eq!(buffer, vec![3, 0xFD, 0xFF, 1]);
+    assert_eq!(
+        SmallVec::<[u8; 8]>::decode(&mut State::from_buffer(buffer)),
+        Ok(vec)
+    );
+}
@@ -0,0 +1,80 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+
+use crate::sparse_matrix::*;
+use crate::*;
+
+// 4x6 matrix
+// [ 10 20  0  0  0  0 ]
+// [  0 30  0 40  0  0 ]
+// [  0  0  0  0  0  0 ]
+// [  0  0 50 60 70 80 ]
+fn matrix() -> SparseMatrix<f32> {
+    SparseMatrix::new(
+        4,
+        6,
+        vec![0, 2, 4, 4, 8],
+        vec![0, 1, 1, 3, 2, 3, 4, 5],
+        vec![10.0, 20.0, 30.0, 40.0, 50.0, 60.0, 70.0, 80.0],
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_sparse_matrix_roundtrip() {
+    let matrix = matrix();
+    let buffer = encode_to_vec_checked(&matrix).unwrap();
+    // dims, entries per row, column deltas
+    assert_eq!(buffer[..14], [4, 6, 2, 2, 0, 4, 0, 1, 1, 2, 2, 1, 1, 1]);
+
+    let mut state = State::from_buffer(buffer);
+    let decoded = SparseMatrix::<f32>::decode(&mut state).unwrap();
+    assert_eq!(state.start, state.end);
+    assert_eq!(decoded, matrix);
+    assert_eq!(decoded.dims(), (4, 6));
+    assert_eq!(decoded.row_ptr(), [0, 2, 4, 4, 8]);
+    assert_eq!(decoded.col_indices(), [0, 1, 1, 3, 2, 3, 4, 5]);
+
+    let entries = [
+        (0, 0, 10.0),
+        (0, 1, 20.0),
+        (1, 1, 30.0),
+        (1, 3, 40.0),
+        (3, 2, 50.0),
+        (3, 3, 60.0),
+        (3, 4, 70.0),
+        (3, 5, 80.0),
+    ];
+    for (row, col, value) in entries {
+        assert_eq!(decoded.get(row, col), Some(&value));
+    }
+    assert_eq!(decoded.get(0, 2), None);
+    assert_eq!(decoded.get(2, 0), None);
+    assert_eq!(decoded.get(4, 0), None);
+    assert_eq!(decoded.get(usize::MAX, 0), None);
+}
+
+#[test]
+fn test_sparse_matrix_invalid() {
+    // column index out of range
+    assert!(SparseMatrix::new(1, 2, vec![0, 1], vec![2], vec![1u8]).is_none());
+    // columns not increasing
+    assert!(SparseMatrix::new(1, 3, vec![0, 2], vec![1, 1], vec![1u8, 2]).is_none());
+    // row pointers not increasing
+    assert!(SparseMatrix::new(2, 3, vec![0, 5, 2], vec![0, 1], vec![1u8, 2]).is_none());
+    // row pointers do not match values
+    assert!(SparseMatrix::new(1, 3, vec![0, 1], vec![0, 1], vec![1u8, 2]).is_none());
+
+    // 1x2 matrix with a duplicate column
+    let mut state = State::from_buffer(vec![1, 2, 2, 1, 0, 2, 1, 2]);
+    assert_eq!(
+        SparseMatrix::<u8>::decode(&mut state),
+        Err(DecodeError::TypeMismatch)
+    );
+    // 1x2 matrix with column 2
+    let mut state = State::from_buffer(vec![1, 2, 1, 2, 1, 1]);
+    assert_eq!(
+        SparseMatrix::<u8>::decode(&mut state),
+        Err(DecodeError::TypeMismatch)
+    );
+}
@@ -0,0 +1,657 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+
+use crate::*;
+
+//
+// append mode
+//
+
+#[test]
+fn test_reserve() {
+    let mut state = State::new();
+    state.reserve(2);
+    assert_eq!(
+        state,
+        State {
+            start: 0,
+            end: 2,
+            buffer: Some(vec![0, 0]),
+            ..Default::default()
+        }
+    );
+    assert_eq!(state.write(&[1, 2]), Ok(()));
+    state.reserve(1);
+    assert_eq!(
+        state,
+        State {
+            start: 2,
+            end: 3,
+            buffer: Some(vec![1, 2, 0]),
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn test_encode_append() {
+    let mut state = State::new();
+    assert_eq!(state.encode_append(&42u8), Ok(()));
+    assert_eq!(state.encode_append(&"hi"), Ok(()));
+    assert_eq!(state.encode_append(&4200u16), Ok(()));
+    assert_eq!(state.encode_append(&true), Ok(()));
+    assert_eq!(
+        state,
+        State {
+            start: 8,
+            end: 8,
+            buffer: Some(vec![42, 2, b'h', b'i', 0xFD, 0x68, 0x10, 1]),
+            ..Default::default()
+        }
+    );
+
+    state.start = 0;
+    assert_eq!(u8::decode(&mut state), Ok(42));
+    assert_eq!(String::decode(&mut state), Ok("hi".into()));
+    assert_eq!(u16::decode(&mut state), Ok(4200));
+    assert_eq!(bool::decode(&mut state), Ok(true));
+    assert_eq!(state.start, state.end);
+}
+
+#[test]
+fn test_append_state() {
+    let mut first = State::new();
+    42u32.pre_encode(&mut first);
+    first.alloc();
+    assert_eq!(42u32.encode(&mut first), Ok(()));
+
+    let mut second = State::new();
+    "hi".pre_encode(&mut second);
+    second.alloc();
+    assert_eq!("hi".encode(&mut second), Ok(()));
+
+    let mut merged = State::new();
+    merged.append_state(&first);
+    merged.append_state(&second);
+    assert_eq!(
+        merged,
+        State {
+            start: 8,
+            end: 8,
+            buffer: Some(vec![0xFE, 42, 0, 0, 0, 2, b'h', b'i']),
+            ..Default::default()
+        }
+    );
+
+    merged.start = 0;
+    assert_eq!(u32::decode(&mut merged), Ok(42));
+    assert_eq!(String::decode(&mut merged), Ok("hi".into()));
+    assert_eq!(merged.start, merged.end);
+}
+
+#[test]
+fn test_truncate() {
+    let mut state = State::new();
+    state.reserve(10);
+    assert_eq!(state.write(&[1, 2, 3, 4, 5, 6]), Ok(()));
+
+    state.truncate(6);
+    assert_eq!(
+        state,
+        State {
+            start: 6,
+            end: 6,
+            buffer: Some(vec![1, 2, 3, 4, 5, 6]),
+            ..Default::default()
+        }
+    );
+
+    // growing is not possible with truncate
+    state.truncate(8);
+    assert_eq!(state.end, 6);
+
+    state.truncate(2);
+    assert_eq!(
+        state,
+        State {
+            start: 2,
+            end: 2,
+            buffer: Some(vec![1, 2]),
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn test_to_hex() {
+    let mut state = State::new();
+    assert_eq!(state.to_hex(), "");
+    assert_eq!(state.to_hex_with_cursor(), "|");
+
+    assert_eq!(state.encode_append(&42u8), Ok(()));
+    assert_eq!(state.encode_append(&4200u16), Ok(()));
+    assert_eq!(state.to_hex(), "2a fd 68 10");
+    assert_eq!(state.to_hex_with_cursor(), "2a fd 68 10 |");
+
+    state.start = 1;
+    assert_eq!(state.to_hex_with_cursor(), "2a | fd 68 10");
+}
+
+//
+// reading
+//
+
+#[test]
+fn test_read_next_respects_end() {
+    let mut state = State {
+        start: 0,
+        end: 3,
+        buffer: Some(vec![1, 2, 3, 4, 5, 6]),
+        ..Default::default()
+    };
+    assert_eq!(state.read_next(4), Err(DecodeError::BufferTooSmall));
+    assert_eq!(state.start, 0);
+    assert_eq!(state.read_next(3), Ok(&[1u8, 2, 3][..]));
+    assert_eq!(state.read_next(1), Err(DecodeError::BufferTooSmall));
+
+    // u32 needs 5 bytes, only 4 are left before end
+    let mut state = State {
+        start: 0,
+        end: 4,
+        buffer: Some(vec![0xFE, 1, 0, 0, 0]),
+        ..Default::default()
+    };
+    assert_eq!(u32::decode(&mut state), Err(DecodeError::BufferTooSmall));
+}
+
+#[test]
+fn test_read_remaining() {
+    let mut state = State::new();
+    42u32.pre_encode(&mut state);
+    state.end += 3;
+    state.alloc();
+    assert_eq!(42u32.encode(&mut state), Ok(()));
+    assert_eq!(state.write(&[7, 8, 9]), Ok(()));
+
+    state.start = 0;
+    assert_eq!(u32::decode(&mut state), Ok(42));
+    assert_eq!(state.read_remaining(), Ok(&[7u8, 8, 9][..]));
+    assert_eq!(state.start, state.end);
+    assert_eq!(state.read_remaining(), Ok(&[][..]));
+}
+
+#[test]
+fn test_expect_magic() {
+    let mut state = State::new();
+    for chunk in ["first", "second"] {
+        state.encode_append(b"CHNK").unwrap();
+        state.encode_append(&chunk).unwrap();
+    }
+
+    state.start = 0;
+    assert_eq!(state.expect_magic(b"CHNK"), Ok(()));
+    assert_eq!(state.start, 4);
+    assert_eq!(String::decode(&mut state), Ok("first".into()));
+    assert_eq!(state.expect_magic(b"TAG!"), Err(DecodeError::BadMagic));
+    assert_eq!(state.start, 10);
+    assert_eq!(state.expect_magic(b"CHNK"), Ok(()));
+    assert_eq!(String::decode(&mut state), Ok("second".into()));
+    assert_eq!(
+        state.expect_magic(b"CHNK"),
+        Err(DecodeError::BufferTooSmall)
+    );
+}
+
+#[test]
+fn test_decode_tagged() {
+    use std::collections::HashMap;
+
+    #[derive(Debug, PartialEq)]
+    enum Value {
+        Number(u32),
+        Text(String),
+    }
+
+    let mut decoders: HashMap<u8, fn(&mut State) -> DecodeResultT<Value>> = HashMap::new();
+    decoders.insert(1, |state| u32::decode(state).map(Value::Number));
+    decoders.insert(2, |state| String::decode(state).map(Value::Text));
+
+    let mut state = State::new();
+    state.encode_append(&1u8).unwrap();
+    state.encode_append(&7u32).unwrap();
+    state.encode_append(&2u8).unwrap();
+    state.encode_append(&"seven").unwrap();
+    state.encode_append(&3u8).unwrap();
+
+    state.start = 0;
+    assert_eq!(state.decode_tagged(&decoders), Ok(Value::Number(7)));
+    assert_eq!(
+        state.decode_tagged(&decoders),
+        Ok(Value::Text("seven".into()))
+    );
+    // unknown tag is not consumed
+    let position = state.start;
+    assert_eq!(
+        state.decode_tagged(&decoders),
+        Err(DecodeError::TypeMismatch)
+    );
+    assert_eq!(state.start, position);
+}
+
+#[test]
+fn test_decode_with_offset() {
+    #[derive(Debug, PartialEq)]
+    struct Record {
+        id: u8,
+        name: String,
+        value: u32,
+    }
+
+    impl Decode for Record {
+        fn decode(state: &mut State) -> DecodeResultT<Self> {
+            Ok(Self {
+                id: u8::decode(state)?,
+                name: String::decode(state)?,
+                value: u32::decode(state)?,
+            })
+        }
+    }
+
+    let mut state = State::from_buffer(vec![7, 3, b'a', b'b', b'c', 0xFE, 1, 0, 0, 0]);
+    assert_eq!(
+        state.decode_with_offset::<Record>(),
+        Ok(Record {
+            id: 7,
+            name: "abc".into(),
+            value: 1,
+        })
+    );
+
+    // u32 field is truncated
+    let mut state = State::from_buffer(vec![7, 3, b'a', b'b', b'c', 0xFE, 1, 0]);
+    let error = state.decode_with_offset::<Record>().unwrap_err();
+    assert_eq!(error, DecodeError::BufferTooSmall.at(5));
+    assert_eq!(
+        error.to_string(),
+        "BufferTooSmall at offset 5: buffer is too small to decode the expected type"
+    );
+}
+
+#[test]
+fn test_take_remaining_raw() {
+    let mut state = State::new();
+    state.encode_append(&42u32).unwrap();
+    state.encode_append(&Raw::Slice(b"payload")).unwrap();
+
+    state.start = 0;
+    assert_eq!(u32::decode(&mut state), Ok(42));
+    assert_eq!(state.take_remaining_raw(), Ok(Raw::Slice(b"payload")));
+    assert_eq!(state.start, state.end);
+    assert_eq!(state.take_remaining_raw(), Ok(Raw::Slice(&[])));
+}
+
+#[test]
+fn test_read_lp_slice() {
+    let mut state = State::new();
+    "blob".pre_encode(&mut state);
+    vec![0xFEu8, 1, 0].pre_encode(&mut state);
+    Vec::<u8>::new().pre_encode(&mut state);
+    state.alloc();
+    assert_eq!("blob".encode(&mut state), Ok(()));
+    assert_eq!(vec![0xFEu8, 1, 0].encode(&mut state), Ok(()));
+    assert_eq!(Vec::<u8>::new().encode(&mut state), Ok(()));
+
+    // forward the length-prefixed fields byte-for-byte
+    state.start = 0;
+    let mut forwarded = State::new();
+    while state.remaining() > 0 {
+        let blob = state.read_lp_slice().unwrap().to_vec();
+        forwarded.encode_append(&blob).unwrap();
+    }
+    assert_eq!(forwarded.buffer, state.buffer);
+
+    state.start = 0;
+    assert_eq!(state.read_lp_slice(), Ok(&b"blob"[..]));
+    assert_eq!(state.read_lp_slice(), Ok(&[0xFE, 1, 0][..]));
+    assert_eq!(state.read_lp_slice(), Ok(&[][..]));
+    assert_eq!(state.start, state.end);
+
+    let mut state = State::from_buffer(vec![4, 1, 2]);
+    assert_eq!(
+        state.read_lp_slice(),
+        Err(DecodeError::InvalidLength {
+            declared: 4,
+            available: 2
+        })
+    );
+}
+
+//
+// scoped decode
+//
+
+#[test]
+fn test_decode_scoped_exact() {
+    let mut state = State::new();
+    // region: length prefix + u32 (5 bytes), followed by a u8 of the parent
+    5usize.pre_encode(&mut state);
+    42u32.pre_encode(&mut state);
+    7u8.pre_encode(&mut state);
+    state.alloc();
+    assert_eq!(5usize.encode(&mut state), Ok(()));
+    assert_eq!(42u32.encode(&mut state), Ok(()));
+    assert_eq!(7u8.encode(&mut state), Ok(()));
+
+    state.start = 0;
+    assert_eq!(state.decode_scoped::<u32>(), Ok(42));
+    assert_eq!(state.start, 6);
+    assert_eq!(state.end, 7);
+    assert_eq!(u8::decode(&mut state), Ok(7));
+}
+
+#[test]
+fn test_decode_scoped_under_read() {
+    let mut state = State::new();
+    // region is 2 bytes long but the decoded u8 consumes only 1
+    2usize.pre_encode(&mut state);
+    state.end += 2;
+    state.alloc();
+    assert_eq!(2usize.encode(&mut state), Ok(()));
+    assert_eq!(state.write(&[1, 2]), Ok(()));
+
+    state.start = 0;
+    assert_eq!(state.decode_scoped::<u8>(), Err(DecodeError::TypeMismatch));
+}
+
+#[test]
+fn test_decode_scoped_over_read() {
+    let mut state = State::new();
+    // region is 2 bytes long but contains the start of an encoded u32
+    2usize.pre_encode(&mut state);
+    42u32.pre_encode(&mut state);
+    state.alloc();
+    assert_eq!(2usize.encode(&mut state), Ok(()));
+    assert_eq!(42u32.encode(&mut state), Ok(()));
+
+    state.start = 0;
+    assert_eq!(
+        state.decode_scoped::<u32>(),
+        Err(DecodeError::BufferTooSmall)
+    );
+    assert_eq!(state.end, 6);
+}
+
+#[test]
+fn test_decode_scoped_length_too_large() {
+    let mut state = State::new();
+    10usize.pre_encode(&mut state);
+    42u8.pre_encode(&mut state);
+    state.alloc();
+    assert_eq!(10usize.encode(&mut state), Ok(()));
+    assert_eq!(42u8.encode(&mut state), Ok(()));
+
+    state.start = 0;
+    assert_eq!(
+        state.decode_scoped::<u8>(),
+        Err(DecodeError::InvalidLength {
+            declared: 10,
+            available: 1
+        })
+    );
+}
+
+//
+// forward compatibility
+//
+
+/// first version of a message
+#[derive(Debug, PartialEq)]
+struct MessageV1 {
+    id: u32,
+    name: String,
+}
+
+impl Decode for MessageV1 {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        Ok(Self {
+            id: u32::decode(state)?,
+            name: String::decode(state)?,
+        })
+    }
+}
+
+/// second version of a message with an additional trailing field
+struct MessageV2 {
+    id: u32,
+    name: String,
+    flag: bool,
+}
+
+impl Encode for MessageV2 {
+    fn pre_encode(&self, state: &mut State) {
+        self.id.pre_encode(state);
+        self.name.pre_encode(state);
+        self.flag.pre_encode(state);
+    }
+
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        self.id.encode(state)?;
+        self.name.encode(state)?;
+        self.flag.encode(state)
+    }
+}
+
+fn message_v2() -> MessageV2 {
+    MessageV2 {
+        id: 7,
+        name: "v2".into(),
+        flag: true,
+    }
+}
+
+#[test]
+fn test_decode_lenient() {
+    let buffer = encode_to_vec(&message_v2()).unwrap();
+    assert_eq!(
+        decode_lenient::<MessageV1>(&buffer),
+        Ok(MessageV1 {
+            id: 7,
+            name: "v2".into()
+        })
+    );
+}
+
+#[test]
+fn test_decode_exact() {
+    let buffer = encode_to_vec(&message_v2()).unwrap();
+    // the trailing flag of MessageV2 is rejected
+    assert_eq!(
+        decode_exact::<MessageV1>(&buffer),
+        Err(DecodeError::TypeMismatch)
+    );
+    assert_eq!(
+        decode_exact::<MessageV1>(&buffer[..buffer.len() - 1]),
+        Ok(MessageV1 {
+            id: 7,
+            name: "v2".into()
+        })
+    );
+}
+
+#[test]
+fn test_decode_scoped_lenient() {
+    let message = message_v2();
+    let mut state = State::new();
+    encoded_size(&message).pre_encode(&mut state);
+    message.pre_encode(&mut state);
+    42u8.pre_encode(&mut state);
+    state.alloc();
+    assert_eq!(encoded_size(&message).encode(&mut state), Ok(()));
+    assert_eq!(message.encode(&mut state), Ok(()));
+    assert_eq!(42u8.encode(&mut state), Ok(()));
+
+    state.start = 0;
+    assert_eq!(
+        state.decode_scoped::<MessageV1>(),
+        Err(DecodeError::TypeMismatch)
+    );
+
+    state.start = 0;
+    assert_eq!(
+        state.decode_scoped_lenient::<MessageV1>(),
+        Ok(MessageV1 {
+            id: 7,
+            name: "v2".into()
+        })
+    );
+    // the parent continues after the skipped field
+    assert_eq!(u8::decode(&mut state), Ok(42));
+    assert_eq!(state.start, state.end);
+}
+
+//
+// read_count
+//
+
+#[test]
+fn test_read_count() {
+    let mut state = State::from_buffer(vec![2, 0, 0]);
+    assert_eq!(state.read_count(2), Ok(2));
+    assert_eq!(state.start, 1);
+
+    let mut state = State::from_buffer(vec![3, 0, 0]);
+    assert_eq!(
+        state.read_count(8),
+        Err(DecodeError::InvalidLength {
+            declared: 3,
+            available: 2
+        })
+    );
+
+    let mut state = State::from_buffer(vec![2, 0, 0]);
+    assert_eq!(state.read_count(1), Err(DecodeError::ArrayTooLarge));
+}
+
+#[test]
+fn test_collection_length_exceeds_remaining() {
+    // every collection claims 5 elements, but only 2 bytes follow
+    let buffer = vec![5, 0, 0];
+    let invalid_length = DecodeError::InvalidLength {
+        declared: 5,
+        available: 2,
+    };
+    let decode_error =
+        |f: fn(&mut State) -> DecodeError| f(&mut State::from_buffer(buffer.clone()));
+
+    assert_eq!(
+        decode_error(|state| Vec::<u32>::decode(state).unwrap_err()),
+        invalid_length.clone()
+    );
+    assert_eq!(
+        decode_error(|state| Vec::<u8>::decode(state).unwrap_err()),
+        invalid_length.clone()
+    );
+    assert_eq!(
+        decode_error(|state| String::decode(state).unwrap_err()),
+        invalid_length.clone()
+    );
+    assert_eq!(
+        decode_error(|state| Option::<Vec<u8>>::decode(state).unwrap_err()),
+        invalid_length.clone()
+    );
+    assert_eq!(
+        decode_error(|state| U32Array::decode(state).unwrap_err()),
+        invalid_length.clone()
+    );
+    assert_eq!(
+        decode_error(|state| Bytes::decode(state).unwrap_err()),
+        invalid_length.clone()
+    );
+    assert_eq!(
+        decode_error(|state| state.decode_iter::<u8>().err().unwrap()),
+        invalid_length.clone()
+    );
+    assert_eq!(
+        decode_error(|state| state.decode_scoped::<u8>().unwrap_err()),
+        invalid_length.clone()
+    );
+}
+
+#[test]
+fn test_collection_length_exceeds_max() {
+    let count = MAX_ARRAY_DECODE_SIZE + 1;
+    let mut state = State::new();
+    count.pre_encode(&mut state);
+    state.end += count;
+    state.alloc();
+    assert_eq!(count.encode(&mut state), Ok(()));
+
+    state.start = 0;
+    assert_eq!(
+        U32Array::decode(&mut state),
+        Err(DecodeError::ArrayTooLarge)
+    );
+    state.start = 0;
+    assert_eq!(
+        Vec::<u16>::decode(&mut state),
+        Err(DecodeError::ArrayTooLarge)
+    );
+}
+
+#[test]
+fn test_invalid_length() {
+    // declared length exceeds the buffer
+    let mut state = State::from_buffer(vec![200, b'a', b'b']);
+    let error = String::decode(&mut state).unwrap_err();
+    assert_eq!(
+        error,
+        DecodeError::InvalidLength {
+            declared: 200,
+            available: 2
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "the length prefix declares 200 elements, but only 2 bytes are available"
+    );
+
+    // truncated fixed width field
+    let buffer = encode_to_vec(&u64::MAX).unwrap();
+    let mut state = State::from_buffer(buffer[..5].to_vec());
+    assert_eq!(u64::decode(&mut state), Err(DecodeError::BufferTooSmall));
+    let mut state = State::from_buffer(vec![1, 2, 3]);
+    assert_eq!(
+        Fixed32::decode(&mut state),
+        Err(DecodeError::BufferTooSmall)
+    );
+}
+
+#[test]
+fn test_encode_at() {
+    // placeholder for the payload length, followed by the payload
+    let mut state = State::new();
+    state.encode_append(&FixedU32(0)).unwrap();
+    state.encode_append(&"payload").unwrap();
+    let position = state.start;
+
+    let length = (state.end - 4) as u32;
+    assert_eq!(state.encode_at(0, &FixedU32(length)), Ok(()));
+    assert_eq!(state.start, position);
+
+    state.start = 0;
+    assert_eq!(FixedU32::decode(&mut state), Ok(FixedU32(8)));
+    assert_eq!(String::decode(&mut state), Ok("payload".into()));
+
+    // the value does not fit into the written region
+    let end = state.end;
+    assert_eq!(
+        state.encode_at(end - 3, &FixedU32(1)),
+        Err(EncodeError::BufferTooSmall)
+    );
+    assert_eq!(
+        state.encode_at(end + 1, &0u8),
+        Err(EncodeError::BufferTooSmall)
+    );
+    assert_eq!(state.end, end);
+    assert_eq!(state.encode_at(end - 4, &FixedU32(1)), Ok(()));
+}
@@ -0,0 +1,101 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+
+use crate::trie::*;
+use crate::*;
+
+fn roundtrip(trie: &StringTrie) -> Vec<u8> {
+    let buffer = encode_to_vec_checked(trie).unwrap();
+    let mut state = State::from_buffer(buffer.clone());
+    assert_eq!(StringTrie::decode(&mut state).as_ref(), Ok(trie));
+    assert_eq!(state.start, state.end);
+    buffer
+}
+
+#[test]
+fn test_trie_roundtrip() {
+    let words = [
+        "international",
+        "internationalize",
+        "internet",
+        "interval",
+        "intern",
+        "car",
+        "card",
+        "",
+        "über",
+        "übel",
+    ];
+    let trie: StringTrie = words.into_iter().collect();
+    let buffer = roundtrip(&trie);
+
+    let decoded = StringTrie::decode(&mut State::from_buffer(buffer.clone())).unwrap();
+    let mut sorted: Vec<String> = words.iter().map(|word| word.to_string()).collect();
+    sorted.sort();
+    assert_eq!(decoded.0.into_iter().collect::<Vec<_>>(), sorted);
+
+    // shared prefixes are smaller than the flat list
+    assert!(buffer.len() < encoded_size(&sorted));
+}
+
+#[test]
+fn test_trie_layout() {
+    let trie: StringTrie = ["ab", "ac"].into_iter().collect();
+    assert_eq!(
+        encode_to_vec(&trie),
+        Ok(vec![
+            0, 1, // root
+            1, b'a', 0, 2, // "a"
+            1, b'b', 1, 0, // "ab"
+            1, b'c', 1, 0, // "ac"
+        ])
+    );
+
+    roundtrip(&StringTrie::default());
+    roundtrip(&[""].into_iter().collect());
+}
+
+#[test]
+fn test_trie_deep() {
+    // every word is a prefix of the next one
+    let trie: StringTrie = (1..5000).map(|length| "a".repeat(length)).collect();
+    roundtrip(&trie);
+}
+
+#[test]
+fn test_trie_deep_chain_decode() {
+    // a long chain of inner nodes must decode in linear time
+    let depth = 200_000;
+    let mut buffer = Vec::with_capacity(5 * depth + 2);
+    for _ in 0..depth {
+        buffer.extend_from_slice(&[0, 1, 2, b'a', b'b']);
+    }
+    buffer.extend_from_slice(&[1, 0]);
+    let mut state = State::from_buffer(buffer.clone());
+    let trie = StringTrie::decode(&mut state).unwrap();
+    assert_eq!(trie.0.into_iter().collect::<Vec<_>>(), ["ab".repeat(depth)]);
+    assert_eq!(state.start, state.end);
+
+    // and a truncated chain fails without decoding past the buffer
+    buffer.truncate(buffer.len() - 2);
+    assert_eq!(
+        StringTrie::decode(&mut State::from_buffer(buffer)),
+        Err(DecodeError::BufferTooSmall)
+    );
+}
+
+#[test]
+fn test_trie_invalid() {
+    // empty edge label
+    let mut state = State::from_buffer(vec![0, 1, 0, 1, 0]);
+    assert_eq!(
+        StringTrie::decode(&mut state),
+        Err(DecodeError::TypeMismatch)
+    );
+    // missing child
+    let mut state = State::from_buffer(vec![0, 2, 1, b'a', 1, 0]);
+    assert_eq!(
+        StringTrie::decode(&mut state),
+        Err(DecodeError::BufferTooSmall)
+    );
+}
@@ -0,0 +1,65 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+
+use crate::*;
+
+#[test]
+fn test_unit() {
+    assert_eq!(encoded_size(&()), 0);
+    assert_eq!(encode_to_vec(&()), Ok(vec![]));
+
+    let mut state = State::from_buffer(vec![]);
+    assert_eq!(<()>::decode(&mut state), Ok(()));
+    assert_eq!(state.start, 0);
+}
+
+#[test]
+fn test_single_element_tuple() {
+    // (A,) is encoded exactly like A
+    assert_eq!(encode_to_vec(&(5u32,)), encode_to_vec(&5u32));
+    assert_eq!(encode_to_vec(&("a",)), encode_to_vec(&"a"));
+
+    let buffer = encode_to_vec(&(5u32,)).unwrap();
+    let mut state = State::from_buffer(buffer.clone());
+    assert_eq!(<(u32,)>::decode(&mut state), Ok((5,)));
+    assert_eq!(state.start, state.end);
+
+    let mut state = State::from_buffer(buffer);
+    assert_eq!(u32::decode(&mut state), Ok(5));
+    assert_eq!(state.start, state.end);
+}
+
+#[test]
+fn test_pair() {
+    let pair = (300u16, String::from("x"));
+    let buffer = encode_to_vec(&pair).unwrap();
+    assert_eq!(buffer, vec![0xFD, 0x2C, 0x01, 1, b'x']);
+    assert_eq!(
+        <(u16, String)>::decode(&mut State::from_buffer(buffer)),
+        Ok(pair)
+    );
+}
+
+#[test]
+fn test_tuple_mixed() {
+    let value = (1u8, "hi".to_string(), true, 3.5f64);
+    let buffer = encode_to_vec(&value).unwrap();
+    assert_eq!(buffer.len(), 1 + 3 + 1 + 8);
+    assert_eq!(buffer[..5], [1, 2, b'h', b'i', 1]);
+    let mut state = State::from_buffer(buffer);
+    assert_eq!(<(u8, String, bool, f64)>::decode(&mut state), Ok(value));
+    assert_eq!(state.start, state.end);
+}
+
+#[test]
+fn test_tuple_arity_12() {
+    let value = (
+        1u8, 2u8, 3u8, 4u8, 5u8, 6u8, 7u8, 8u8, 9u8, 10u8, 11u8, 12u8,
+    );
+    let buffer = encode_to_vec(&value).unwrap();
+    assert_eq!(buffer, (1..=12).collect::<Vec<u8>>());
+    assert_eq!(
+        <(u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8)>::decode(&mut State::from_buffer(buffer)),
+        Ok(value)
+    );
+}
@@ -16,6 +16,7 @@ mod tests {
                 start: 0,
                 end: 1,
                 buffer: None,
+                ..Default::default()
             }
         );
         state.end = 0;
@@ -26,6 +27,7 @@ mod tests {
                 start: 0,
                 end: 1,
                 buffer: None,
+                ..Default::default()
             }
         );
         state.end = 0;
@@ -36,6 +38,7 @@ mod tests {
                 start: 0,
                 end: 3,
                 buffer: None,
+                ..Default::default()
             }
         );
     }
@@ -51,6 +54,7 @@ mod tests {
                 start: 0,
                 end: 3,
                 buffer: None,
+                ..Default::default()
             }
         );
     }
@@ -66,6 +70,7 @@ mod tests {
                 start: 0,
                 end: 5,
                 buffer: None,
+                ..Default::default()
             }
         );
     }
@@ -81,6 +86,7 @@ mod tests {
                 start: 0,
                 end: 9,
                 buffer: None,
+                ..Default::default()
             }
         );
     }
@@ -100,6 +106,7 @@ mod tests {
                 start: 1,
                 end: 1,
                 buffer: Some(vec![42]),
+                ..Default::default()
             }
         );
         state.start = 0;
@@ -110,6 +117,7 @@ mod tests {
                 start: 1,
                 end: 1,
                 buffer: Some(vec![0xFC]),
+                ..Default::default()
             }
         );
 
@@ -124,6 +132,7 @@ mod tests {
                 start: 3,
                 end: 3,
                 buffer: Some(vec![U16_PREFIX, 0xFD, 0]),
+                ..Default::default()
             }
         );
     }
@@ -145,6 +154,7 @@ mod tests {
                 buffer: Some(vec![
                     U16_PREFIX, 0xFD, 0xFF, // u16
                 ]),
+                ..Default::default()
             }
         );
     }
@@ -166,6 +176,7 @@ mod tests {
                 buffer: Some(vec![
                     U32_PREFIX, 0xFD, 0xFF, 0xFF, 0xFF, // u32
                 ]),
+                ..Default::default()
             }
         );
     }
@@ -187,7 +198,151 @@ mod tests {
                 buffer: Some(vec![
                     U64_PREFIX, 0xFD, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF
                 ]),
+                ..Default::default()
             }
         );
     }
+
+    #[test]
+    fn test_uint128() {
+        let mut state = State::new();
+
+        let value: u128 = (1 << 64) | 0x0102;
+        value.pre_encode(&mut state);
+        u128::MAX.pre_encode(&mut state);
+        assert_eq!(state.end, 34);
+
+        state.alloc();
+        assert_eq!(value.encode(&mut state), Ok(()));
+        assert_eq!(u128::MAX.encode(&mut state), Ok(()));
+        let buffer = state.buffer.as_ref().unwrap();
+        // lower 64 bits first, followed by the upper 64 bits
+        assert_eq!(
+            buffer[..17],
+            [
+                U128_PREFIX,
+                0x02,
+                0x01,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                1,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0
+            ]
+        );
+        assert_eq!(buffer[17..], [&[U128_PREFIX][..], &[0xFF; 16]].concat());
+
+        state.start = 0;
+        assert_eq!(u128::decode(&mut state), Ok(value));
+        assert_eq!(u128::decode(&mut state), Ok(u128::MAX));
+        assert_eq!(state.start, state.end);
+
+        // a u64 can not be decoded as u128
+        let mut buffer = encode_to_vec(&u64::MAX).unwrap();
+        buffer.resize(17, 0);
+        let mut state = State::from_buffer(buffer);
+        assert_eq!(u128::decode(&mut state), Err(DecodeError::TypeMismatch));
+
+        // and a u128 can not be decoded as any of the fixed width integers
+        for value in [0, 1, 0xFC, u128::MAX] {
+            let buffer = encode_to_vec(&value).unwrap();
+            for result in [
+                decode_lenient::<u16>(&buffer).map(drop),
+                decode_lenient::<i16>(&buffer).map(drop),
+                decode_lenient::<u32>(&buffer).map(drop),
+                decode_lenient::<i32>(&buffer).map(drop),
+                decode_lenient::<u64>(&buffer).map(drop),
+                decode_lenient::<i64>(&buffer).map(drop),
+            ] {
+                assert_eq!(result, Err(DecodeError::TypeMismatch));
+            }
+            // the variable width usize reads the prefix as a single byte value
+            assert_eq!(decode_lenient::<usize>(&buffer), Ok(U128_PREFIX as usize));
+        }
+    }
+
+    #[test]
+    fn test_uint64_decode_high_and_low_word() {
+        let mut state = State::new();
+        let value: u64 = 0x1234_5678_9ABC_DEF0;
+
+        value.pre_encode(&mut state);
+        state.alloc();
+        assert_eq!(value.encode(&mut state), Ok(()));
+        assert_eq!(
+            state.buffer,
+            Some(vec![
+                U64_PREFIX, 0xF0, 0xDE, 0xBC, 0x9A, 0x78, 0x56, 0x34, 0x12
+            ])
+        );
+
+        state.start = 0;
+        assert_eq!(u64::decode(&mut state), Ok(value));
+        assert_eq!(state.start, state.end);
+    }
+
+    #[test]
+    fn test_uint64_upper_word_not_shifted() {
+        let value: u64 = 0xFFFF_FFFF_0000_0001;
+        let buffer = encode_to_vec(&value).unwrap();
+        assert_eq!(
+            buffer,
+            vec![U64_PREFIX, 0x01, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF]
+        );
+
+        let mut state = State::from_buffer(buffer);
+        assert_eq!(u64::decode(&mut state), Ok(value));
+        assert_eq!(state.start, state.end);
+    }
+
+    #[test]
+    fn test_decode_canonical() {
+        for value in [
+            0usize,
+            5,
+            0xFC,
+            0xFD,
+            0xFFFF,
+            0x1_0000,
+            0xFFFF_FFFF,
+            0x1_0000_0000,
+        ] {
+            let mut state = State::from_buffer(encode_to_vec(&value).unwrap());
+            assert_eq!(state.decode_canonical::<u64>(), Ok(value as u64));
+            assert_eq!(state.start, state.end);
+        }
+
+        // 5 encoded as u16, u32 and u64 instead of a single byte
+        for value in [
+            encode_to_vec(&5u16).unwrap(),
+            encode_to_vec(&5u32).unwrap(),
+            encode_to_vec(&5u64).unwrap(),
+            encode_to_vec(&0xFFFFu32).unwrap(),
+            encode_to_vec(&0xFFFF_FFFFu64).unwrap(),
+        ] {
+            let mut state = State::from_buffer(value);
+            assert_eq!(
+                state.decode_canonical::<u64>(),
+                Err(DecodeError::TypeMismatch)
+            );
+            assert_eq!(state.start, 0);
+        }
+
+        // value does not fit into the target type
+        let mut state = State::from_buffer(encode_to_vec(&0x1_0000usize).unwrap());
+        assert_eq!(
+            state.decode_canonical::<u16>(),
+            Err(DecodeError::TypeMismatch)
+        );
+        assert_eq!(state.decode_canonical::<u32>(), Ok(0x1_0000));
+    }
 }
@@ -0,0 +1,20 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+
+//! helpers shared by the tests
+
+use crate::*;
+
+/// encode `value` and decode it back as `T`, requiring the full buffer to be consumed
+pub(crate) fn round_trip<T: Encode + Decode>(value: &T) -> DecodeResultT<T> {
+    let mut state = State::new();
+    value.pre_encode(&mut state);
+    state.alloc();
+    value.encode(&mut state).expect("could not encode");
+    assert_eq!(state.start, state.end);
+
+    state.start = 0;
+    let decoded = T::decode(&mut state);
+    assert_eq!(state.start, state.end);
+    decoded
+}
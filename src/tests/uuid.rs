@@ -0,0 +1,26 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+
+use crate::*;
+use uuid::Uuid;
+
+#[test]
+fn test_uuid_nil() {
+    let value = Uuid::nil();
+    assert_eq!(encode_to_vec(&value), Ok(vec![0; 16]));
+
+    let mut state = State::from_buffer(encode_to_vec(&value).unwrap());
+    assert_eq!(Uuid::decode(&mut state), Ok(value));
+    assert_eq!(state.start, state.end);
+}
+
+#[test]
+fn test_uuid_random() {
+    let value = Uuid::new_v4();
+    assert_eq!(encoded_size(&value), 16);
+    assert_eq!(encode_to_vec(&value), Ok(value.as_bytes().to_vec()));
+
+    let mut state = State::from_buffer(encode_to_vec(&value).unwrap());
+    assert_eq!(Uuid::decode(&mut state), Ok(value));
+    assert_eq!(state.start, state.end);
+}
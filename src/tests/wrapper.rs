@@ -0,0 +1,271 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+
+use crate::*;
+use std::rc::Rc;
+use std::sync::Arc;
+
+//
+// shared ownership wrappers
+//
+
+#[test]
+fn test_arc_string() {
+    let mut state = State::new();
+    let value = Arc::new(String::from("shared"));
+    let other = Arc::clone(&value);
+
+    value.pre_encode(&mut state);
+    other.pre_encode(&mut state);
+    // both references encode the full payload
+    assert_eq!(state.end, 2 * 7);
+    state.alloc();
+    assert_eq!(value.encode(&mut state), Ok(()));
+    assert_eq!(other.encode(&mut state), Ok(()));
+
+    state.start = 0;
+    assert_eq!(Arc::<String>::decode(&mut state), Ok(value.clone()));
+    assert_eq!(Arc::<String>::decode(&mut state), Ok(value));
+    assert_eq!(state.start, state.end);
+}
+
+#[test]
+fn test_rc_u64() {
+    let mut state = State::new();
+    let value = Rc::new(u64::MAX - 2);
+
+    value.pre_encode(&mut state);
+    assert_eq!(state.end, 9);
+    state.alloc();
+    assert_eq!(value.encode(&mut state), Ok(()));
+
+    state.start = 0;
+    assert_eq!(Rc::<u64>::decode(&mut state), Ok(value));
+    assert_eq!(state.start, state.end);
+}
+
+//
+// numeric wrappers
+//
+
+#[test]
+fn test_wrapping() {
+    use std::num::Wrapping;
+
+    let value = Wrapping(u32::MAX);
+    assert_eq!(encode_to_vec(&value), encode_to_vec(&u32::MAX));
+
+    let mut state = State::from_buffer(encode_to_vec(&value).unwrap());
+    let decoded = Wrapping::<u32>::decode(&mut state).unwrap();
+    assert_eq!(decoded, value);
+    assert_eq!(decoded + Wrapping(1), Wrapping(0));
+}
+
+#[test]
+fn test_saturating() {
+    use std::num::Saturating;
+
+    let value = Saturating(-42i16);
+    assert_eq!(encode_to_vec(&value), encode_to_vec(&-42i16));
+
+    let mut state = State::from_buffer(encode_to_vec(&value).unwrap());
+    assert_eq!(Saturating::<i16>::decode(&mut state), Ok(value));
+}
+
+//
+// bounded integers
+//
+
+#[test]
+fn test_bounded() {
+    type Small = Bounded<0, 1000>;
+    assert_eq!(Small::WIDTH, 2);
+    for value in [0, 1, 252, 253, 999, 1000] {
+        let bounded = Small::new(value).unwrap();
+        let buffer = encode_to_vec(&bounded).unwrap();
+        assert_eq!(buffer, (value as u16).to_le_bytes());
+        assert_eq!(Small::decode(&mut State::from_buffer(buffer)), Ok(bounded));
+    }
+    assert_eq!(Small::new(-1), None);
+    assert_eq!(Small::new(1001), None);
+
+    // 1001 is out of range
+    let mut state = State::from_buffer(vec![0xE9, 0x03]);
+    assert_eq!(Small::decode(&mut state), Err(DecodeError::TypeMismatch));
+}
+
+#[test]
+fn test_bounded_offset() {
+    type Offset = Bounded<-10, 245>;
+    assert_eq!(Offset::WIDTH, 1);
+    let buffer = encode_to_vec(&Offset::new(-10).unwrap()).unwrap();
+    assert_eq!(buffer, vec![0]);
+    let buffer = encode_to_vec(&Offset::new(245).unwrap()).unwrap();
+    assert_eq!(buffer, vec![255]);
+    assert_eq!(
+        Offset::decode(&mut State::from_buffer(buffer)).map(|value| value.get()),
+        Ok(245)
+    );
+
+    type Constant = Bounded<7, 7>;
+    assert_eq!(encode_to_vec(&Constant::new(7).unwrap()), Ok(vec![]));
+    assert_eq!(
+        Constant::decode(&mut State::from_buffer(vec![])),
+        Ok(Constant::new(7).unwrap())
+    );
+
+    type Full = Bounded<{ i64::MIN }, { i64::MAX }>;
+    assert_eq!(Full::WIDTH, 8);
+    for value in [i64::MIN, -1, 0, i64::MAX] {
+        let buffer = encode_to_vec(&Full::new(value).unwrap()).unwrap();
+        assert_eq!(
+            Full::decode(&mut State::from_buffer(buffer)).map(|value| value.get()),
+            Ok(value)
+        );
+    }
+}
+
+//
+// flags
+//
+
+#[test]
+fn test_flags64() {
+    let none = Flags64::default();
+    assert_eq!(encode_to_vec(&none), Ok(vec![0]));
+
+    let all = Flags64(u64::MAX);
+    assert_eq!(encoded_size(&all), 9);
+    // only bitmasks up to 0xFC occupy a single byte
+    assert_eq!(encoded_size(&Flags64(0xFC)), 1);
+    assert_eq!(encoded_size(&Flags64(0xFF)), 3);
+
+    let mut sparse = Flags64::default();
+    sparse.insert(0);
+    sparse.insert(3);
+    sparse.insert(10);
+    sparse.set(5, true);
+    sparse.remove(5);
+    assert!(sparse.get(3) && !sparse.get(5) && !sparse.get(64));
+    // bit 10 requires the u16 prefix
+    assert_eq!(
+        encode_to_vec(&sparse),
+        Ok(vec![U16_PREFIX, 0b0000_1001, 0b100])
+    );
+    assert!(encoded_size(&sparse) < encoded_size(&vec![false; 11]));
+
+    for flags in [none, all, sparse, Flags64(1 << 31), Flags64(1 << 32)] {
+        let mut state = State::from_buffer(encode_to_vec(&flags).unwrap());
+        assert_eq!(Flags64::decode(&mut state), Ok(flags));
+        assert_eq!(state.start, state.end);
+    }
+}
+
+//
+// option
+//
+
+#[test]
+fn test_nullable() {
+    let none: Nullable<u64> = Nullable(None);
+    let some: Nullable<u64> = Nullable(Some(42));
+    assert_eq!(encode_to_vec(&none), Ok(vec![0]));
+    assert_eq!(
+        encode_to_vec(&some),
+        Ok(vec![1, 0xFF, 42, 0, 0, 0, 0, 0, 0, 0])
+    );
+    assert_eq!(
+        Nullable::<u64>::decode(&mut State::from_buffer(vec![0])),
+        Ok(none)
+    );
+    assert_eq!(
+        Nullable::<u64>::decode(&mut State::from_buffer(encode_to_vec(&some).unwrap())),
+        Ok(some)
+    );
+
+    let text = Nullable(Some(String::from("hi")));
+    assert_eq!(encode_to_vec(&text), Ok(vec![1, 2, b'h', b'i']));
+    assert_eq!(
+        Nullable::<String>::decode(&mut State::from_buffer(vec![1, 2, b'h', b'i'])),
+        Ok(text)
+    );
+
+    // an empty buffer is distinct from None, unlike Option<&[u8]>
+    assert_eq!(
+        encode_to_vec(&Nullable(Some(Vec::<u8>::new()))),
+        Ok(vec![1, 0])
+    );
+    assert_eq!(
+        Nullable::<Vec<u8>>::decode(&mut State::from_buffer(vec![1, 0])),
+        Ok(Nullable(Some(vec![])))
+    );
+
+    assert_eq!(
+        Nullable::<u8>::decode(&mut State::from_buffer(vec![2, 5])),
+        Err(DecodeError::TypeMismatch)
+    );
+}
+
+//
+// result
+//
+
+#[test]
+fn test_result() {
+    let ok: Result<u8, String> = Ok(5);
+    let err: Result<u8, String> = Err("failed".into());
+    assert_eq!(encode_to_vec(&ok), Ok(vec![0, 5]));
+    let buffer = encode_to_vec(&err).unwrap();
+    assert_eq!(buffer[..2], [1, 6]);
+
+    assert_eq!(
+        Result::<u8, String>::decode(&mut State::from_buffer(vec![0, 5])),
+        Ok(ok)
+    );
+    assert_eq!(
+        Result::<u8, String>::decode(&mut State::from_buffer(buffer)),
+        Ok(err)
+    );
+    assert_eq!(
+        Result::<u8, String>::decode(&mut State::from_buffer(vec![2, 5])),
+        Err(DecodeError::TypeMismatch)
+    );
+}
+
+#[test]
+fn test_result_infallible() {
+    use std::convert::Infallible;
+
+    let value: Result<u32, Infallible> = Ok(42);
+    let buffer = encode_to_vec(&value).unwrap();
+    assert_eq!(buffer, vec![0, 0xFE, 42, 0, 0, 0]);
+    assert_eq!(
+        Result::<u32, Infallible>::decode(&mut State::from_buffer(buffer)),
+        Ok(value)
+    );
+
+    // the Err variant can never appear on the wire
+    let mut state = State::from_buffer(vec![1]);
+    assert_eq!(
+        Result::<u32, Infallible>::decode(&mut state),
+        Err(DecodeError::TypeMismatch)
+    );
+}
+
+#[test]
+fn test_atomic() {
+    use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+    let counter = AtomicU32::new(0);
+    counter.fetch_add(300, Ordering::Relaxed);
+    assert_eq!(encode_to_vec(&counter), encode_to_vec(&300u32));
+
+    let mut state = State::from_buffer(encode_to_vec(&counter).unwrap());
+    let decoded = AtomicU32::decode(&mut state).unwrap();
+    assert_eq!(decoded.load(Ordering::Relaxed), 300);
+
+    let counter = AtomicU64::new(u64::MAX);
+    let mut state = State::from_buffer(encode_to_vec(&counter).unwrap());
+    let decoded = AtomicU64::decode(&mut state).unwrap();
+    assert_eq!(decoded.load(Ordering::Relaxed), u64::MAX);
+}
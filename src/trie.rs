@@ -0,0 +1,175 @@
+// SPDX-License-Identifier: MIT
+// compact-encoding-rs Authors: see AUTHORS.txt
+//! compact encoding for sets of strings as prefix tree
+//!
+//! Strings with common prefixes, e.g. autocomplete dictionaries, are stored as radix tree
+//! where each prefix is encoded only once. The nodes are encoded depth-first in sorted order:
+//!
+//! * node: bool marking the end of a string, followed by the number of children as usize
+//! * each child: edge label as string (never empty), followed by the child node
+
+use crate::error::*;
+use crate::{Decode, Encode, State, MAX_ARRAY_DECODE_SIZE, MAX_STRING_DECODE_SIZE};
+use std::collections::BTreeSet;
+
+/// compact encoding for a set of strings as prefix tree
+///
+/// The strings are decoded in sorted order, use `.0.into_iter().collect::<Vec<_>>()`
+/// to get a sorted Vec<String>.
+/// The number of strings is limited to MAX_ARRAY_DECODE_SIZE and their total length
+/// to MAX_STRING_DECODE_SIZE when decoding.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StringTrie(pub BTreeSet<String>);
+
+impl<S: Into<String>> FromIterator<S> for StringTrie {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        Self(iter.into_iter().map(Into::into).collect())
+    }
+}
+
+/// element of the depth-first traversal of the prefix tree
+enum TrieItem<'a> {
+    Node { terminal: bool, children: usize },
+    Label(&'a str),
+}
+
+/// return the length in bytes of the longest common prefix of `a` and `b` on char boundaries
+fn common_prefix_len(a: &str, b: &str) -> usize {
+    a.char_indices()
+        .zip(b.chars())
+        .find(|((_, x), y)| x != y)
+        .map(|((index, _), _)| index)
+        .unwrap_or(a.len().min(b.len()))
+}
+
+/// split sorted `words`, which all are longer than `depth`, into groups with the same char at `depth`
+fn split_groups<'a, 'b>(words: &'b [&'a str], depth: usize) -> Vec<&'b [&'a str]> {
+    let mut groups = Vec::new();
+    let mut rest = words;
+    while let Some(first) = rest.first() {
+        let first = first[depth..].chars().next();
+        let end = rest
+            .iter()
+            .position(|word| word[depth..].chars().next() != first)
+            .unwrap_or(rest.len());
+        groups.push(&rest[..end]);
+        rest = &rest[end..];
+    }
+    groups
+}
+
+/// visit all nodes and labels of the prefix tree of sorted, unique `words` depth-first
+///
+/// An explicit stack is used, so deeply nested trees do not overflow the call stack.
+fn walk<'a, F>(words: &[&'a str], mut visit: F) -> EncodeResult
+where
+    F: FnMut(TrieItem<'a>) -> EncodeResult,
+{
+    // pending nodes as (words below the node, byte offset of the node in the words)
+    let mut stack = vec![(words, 0)];
+    let mut root = true;
+    while let Some((words, depth)) = stack.pop() {
+        let depth = if root {
+            root = false;
+            depth
+        } else {
+            // edge label is the common prefix of all words below the child
+            let first = words[0];
+            let last = words[words.len() - 1];
+            let length = common_prefix_len(&first[depth..], &last[depth..]);
+            visit(TrieItem::Label(&first[depth..depth + length]))?;
+            depth + length
+        };
+        let terminal = words.first().is_some_and(|word| word.len() == depth);
+        let groups = split_groups(&words[terminal as usize..], depth);
+        visit(TrieItem::Node {
+            terminal,
+            children: groups.len(),
+        })?;
+        stack.extend(groups.into_iter().rev().map(|group| (group, depth)));
+    }
+    Ok(())
+}
+
+/// compact encoding for StringTrie
+impl Encode for StringTrie {
+    /// allocate the required size in State for current type
+    fn pre_encode(&self, state: &mut State) {
+        let words: Vec<&str> = self.0.iter().map(String::as_str).collect();
+        let _ = walk(&words, |item| {
+            match item {
+                TrieItem::Node { terminal, children } => {
+                    terminal.pre_encode(state);
+                    children.pre_encode(state);
+                }
+                TrieItem::Label(label) => label.pre_encode(state),
+            }
+            Ok(())
+        });
+    }
+
+    /// encode n into state.buffer
+    /// requires state.buffer to be allocated first
+    fn encode(&self, state: &mut State) -> EncodeResult {
+        let words: Vec<&str> = self.0.iter().map(String::as_str).collect();
+        walk(&words, |item| match item {
+            TrieItem::Node { terminal, children } => {
+                terminal.encode(state)?;
+                children.encode(state)
+            }
+            TrieItem::Label(label) => label.encode(state),
+        })
+    }
+}
+
+/// compact decoding for StringTrie
+/// return TypeMismatch for empty edge labels
+impl Decode for StringTrie {
+    fn decode(state: &mut State) -> DecodeResultT<Self> {
+        let mut words = BTreeSet::new();
+        let mut total_length = 0;
+        // open nodes as (length of the prefix of the node, number of children not decoded yet),
+        // sharing one prefix which is truncated when returning to the parent
+        let mut stack: Vec<(usize, usize)> = Vec::new();
+        let mut prefix = String::new();
+        loop {
+            let terminal = bool::decode(state)?;
+            let children = state.read_count(MAX_ARRAY_DECODE_SIZE)?;
+            if terminal {
+                total_length += prefix.len();
+                if words.len() >= MAX_ARRAY_DECODE_SIZE {
+                    return Err(DecodeError::ArrayTooLarge);
+                }
+                if total_length > MAX_STRING_DECODE_SIZE {
+                    return Err(DecodeError::StringTooLarge);
+                }
+                words.insert(prefix.clone());
+            }
+            stack.push((prefix.len(), children));
+
+            // continue with the next pending child of the innermost open node
+            loop {
+                match stack.last_mut() {
+                    None => return Ok(Self(words)),
+                    Some((_, 0)) => {
+                        stack.pop();
+                    }
+                    Some((parent_length, remaining)) => {
+                        *remaining -= 1;
+                        let label = String::decode(state)?;
+                        if label.is_empty() {
+                            return Err(DecodeError::TypeMismatch);
+                        }
+                        prefix.truncate(*parent_length);
+                        // a single prefix is limited like a single string
+                        if prefix.len() + label.len() > MAX_STRING_DECODE_SIZE {
+                            return Err(DecodeError::StringTooLarge);
+                        }
+                        prefix.push_str(&label);
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}